@@ -0,0 +1,52 @@
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use pong_terminal::{level::{self, Level}, world::World, Simulation};
+use rand::{rngs::StdRng, SeedableRng};
+use ratatui::{style::Color, widgets::canvas::Rectangle};
+
+fn on_tick(c: &mut Criterion) {
+    let mut sim = Simulation::new(42);
+    c.bench_function("on_tick classic", |b| b.iter(|| sim.tick()));
+
+    let mut sim = Simulation::procedural(42);
+    c.bench_function("on_tick procedural", |b| b.iter(|| sim.tick()));
+}
+
+fn collisions(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(7);
+    let mut level = Level::generate(16, &mut rng);
+    let target = level.bricks[level.bricks.len() / 2].rect.clone();
+    let mut world = World::default();
+    level.lay_out(&mut world);
+
+    // below every brick row, so each call scans the whole layout without removing anything
+    let miss = Rectangle {
+        x: 80.0,
+        y: 20.0,
+        width: 5.0,
+        height: 5.0,
+        color: Color::Red,
+    };
+    c.bench_function("brick collision miss", |b| {
+        b.iter(|| level::hit(&mut world, black_box(&miss)))
+    });
+
+    let hit = Rectangle {
+        x: target.x + 1.0,
+        y: target.y - 2.0,
+        ..miss
+    };
+    c.bench_function("brick collision hit", |b| {
+        b.iter_batched(
+            || world.clone(),
+            |mut world| level::hit(&mut world, black_box(&hit)),
+            BatchSize::SmallInput,
+        )
+    });
+
+    c.bench_function("level generate", |b| {
+        b.iter(|| Level::generate(black_box(16), &mut rng))
+    });
+}
+
+criterion_group!(benches, on_tick, collisions);
+criterion_main!(benches);
@@ -0,0 +1,25 @@
+use rand::Rng;
+use ratatui::widgets::canvas::Rectangle;
+
+use crate::court::Court;
+
+// Extremely simple opponent: drift along with the ball's horizontal direction, some of the time
+pub fn follow<R: Rng>(
+    paddle: &mut Rectangle,
+    ball: &Rectangle,
+    dir_x: bool,
+    speed: f64,
+    playground: Court,
+    rng: &mut R,
+) {
+    let ball_bounds = [ball.x - ball.width / 2.0, ball.x + ball.width / 2.0];
+    let paddle_bounds = [paddle.x - paddle.width / 2.0, paddle.x + paddle.width / 2.0];
+
+    if rng.gen_range(0..9) > 4 {
+        if dir_x && paddle_bounds[0] < ball_bounds[1] && paddle.x + paddle.width < playground.right() {
+            paddle.x += speed;
+        } else if !dir_x && paddle_bounds[1] > ball_bounds[0] && paddle.x > playground.left() {
+            paddle.x -= speed;
+        }
+    }
+}
@@ -0,0 +1,1474 @@
+use std::{
+    collections::VecDeque,
+    f64::consts::SQRT_2,
+    io,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crossterm::event::KeyCode;
+use log::{debug, info, warn};
+use rand::{
+    distributions::{Distribution, Uniform},
+    Rng, SeedableRng,
+};
+use rand_chacha::ChaCha12Rng;
+use ratatui::{style::Color, widgets::canvas::Rectangle};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ai,
+    boss::{self, Attack, Boss, Landed},
+    collision,
+    combo::ComboMeter,
+    config::Config,
+    court::Court,
+    debug::Diagnostics,
+    editor::Editor,
+    events::{GameEvent, Paddle},
+    history::{History, Input, Snapshot},
+    instant::{Frame, InstantReplay},
+    leaderboard::{Entry, Leaderboard, LEADERBOARD_FILE},
+    level::{self, BrickKind, Level, Side, ZoneKind},
+    locale::Locale,
+    menu::Menu,
+    modifiers::{self, Modifiers, Visibility},
+    mods::{self, Mods, Tuning},
+    palette::{self, Palette},
+    profile::{Profile, ProfileMenu},
+    progress::{LevelProgress, LEVEL_TICKS},
+    results::{Results, CELEBRATION_TICKS},
+    save,
+    screen::{Navigator, Screen},
+    series::{Series, Winner},
+    settings::{self as settings_file, Settings, SettingsMenu, PADDLE_SPEEDS},
+    sound::{self, AudioBackend, Silent},
+    splits::{self, Splits},
+    summary::{GameMode, Record, Summary},
+    targets::Targets,
+    telemetry::{Row, Sample, StatsStream, Telemetry},
+    toast::Toasts,
+    ui::animation::Impacts,
+    world::{Entity, Kind, Velocity, World},
+};
+
+// Sizes that still leave a playable court, for both the command line and practice mode
+pub const BALL_SIZES: RangeInclusive<f64> = 1.0..=20.0;
+pub const PADDLE_WIDTHS: RangeInclusive<f64> = 2.0..=75.0;
+pub const PADDLE_HEIGHTS: RangeInclusive<f64> = 1.0..=10.0;
+pub const SPEEDS: RangeInclusive<f64> = 0.5..=3.0; //the game speed multiplier
+
+const MIN_PADDLE_WIDTH: f64 = 4.0; //how far --shrink can take the player's paddle
+const SPEED_STEP: f64 = 0.25;
+const BOSS_SPEED_UP: f64 = 0.3; //added to the ball speed by the boss's attack
+pub const PLAYER_Y: f64 = Court::FULL.bottom(); //the player's paddle sits on the end line
+pub const PLAYER_BAND: f64 = 25.0; //how far above its home row --vertical lets the paddle go
+const CPU_TOP: f64 = Court::FULL.top() - 2.0; //the cpu paddle hangs down from here whatever its height
+const NEAR_MISS: f64 = 5.0; //how close past the paddle's edge the ball has to go for slow motion
+const NEAR_MISS_REACH: f64 = 0.05; //of the court's height, how close above the paddle a near miss is
+const CATCH_REACH: f64 = 0.17; //of the court's height above the paddle, where the ball can still be caught
+const CPU_WAKES: f64 = 0.4; //of the way up the court, where the cpu starts after a ball coming its way
+const AUTOPILOT_WAKES: f64 = 0.6; //the same for the player's paddle on autopilot, coming down
+const SLOW_TICKS: u16 = 30;
+pub const TICK: Duration = Duration::from_millis(25); //one step of the game clock, the event loop's tick rate
+pub const TICK_MS: f64 = TICK.as_millis() as f64;
+const SECOND_TICKS: u64 = (1000 / TICK.as_millis()) as u64; //how often --stats-file takes a sample
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RandomSignal {
+    distribution: Uniform<u64>,
+    rng: ChaCha12Rng,
+}
+
+impl RandomSignal {
+    pub(crate) fn new(lower: u64, upper: u64, seed: u64) -> RandomSignal {
+        RandomSignal {
+            distribution: Uniform::new(lower, upper),
+            rng: ChaCha12Rng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Iterator for RandomSignal {
+    type Item = u64;
+    fn next(&mut self) -> Option<u64> {
+        Some(self.distribution.sample(&mut self.rng))
+    }
+}
+
+// How the court is shown, the physics always runs upright with the player at the bottom
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Orientation {
+    Upright,
+    Sideways, //player on the left, cpu on the right
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Menu,
+    Demo,
+    Play,
+    Paused,
+    Edit,
+    Settings,
+    Profiles,
+    InstantReplay,
+}
+
+pub struct App {
+    pub(crate) screens: Navigator,
+    pub(crate) help: bool, //the controls overlay, holds the game still while open
+    pub(crate) screenshot: bool, //keep the next frame drawn
+    pub(crate) exit_code: Option<i32>, //set when a signal ended the game
+    pub(crate) confirm_quit: bool,
+    pub(crate) quitting: bool, //asking whether to quit, over the screen to go back to otherwise
+    pub(crate) focus_pause: bool,
+    pub(crate) paused_by_focus: bool, //resumes when the terminal gets focus back
+    pub(crate) idle_pause: u64, //ticks of play without a key before pausing, 0 never does
+    pub(crate) idle_ticks: u64,
+    pub(crate) idle: bool, //paused for being idle, asks whether the player is still there
+    pub(crate) screensaver: bool, //an endless demo that any key quits
+    pub(crate) autopilot: bool, //the cpu plays the player's paddle in a real game too
+    pub(crate) menu: Menu,
+    pub(crate) profile: Option<Profile>,
+    pub(crate) profiles: ProfileMenu,
+    pub(crate) settings: Settings,
+    pub(crate) settings_menu: SettingsMenu,
+    pub(crate) debug: Diagnostics,
+    pub(crate) history: History,
+    pub(crate) replay: Option<VecDeque<(u64, Input)>>,
+    pub(crate) instant: InstantReplay,
+
+    pub(crate) ball: Entity, //the ball and both paddles live in the world, like everything else on the court
+    pub(crate) catchable: bool, //low enough over the paddle to be returned
+    pub(crate) visibility: Visibility, //blinks the ball with the blink modifier
+    pub(crate) board: Entity,
+    pub(crate) cpu: Entity,
+    
+    pub(crate) playground: Court,
+    pub(crate) paddle_size: (f64, f64), //what the player's paddle returns to on reset
+    pub(crate) practice: bool,
+    pub(crate) hardcore: bool, //one miss ends the run, which goes on the leaderboard instead of the rating
+    pub(crate) run_rank: Option<(usize, usize)>, //where the last hardcore run placed on its seed, of how many
+    pub(crate) modifiers: Modifiers, //the match's own, taken from the settings when it starts
+    pub(crate) vertical: bool,
+    pub(crate) orientation: Orientation,
+    pub(crate) max_speed: f64,
+    pub(crate) slow_motion: f64, //time scale after a near miss, 1 when switched off
+    pub(crate) slow_ticks: u16, //left of the current slow motion
+    pub(crate) speed: f64, //everything that moves goes this much faster, 1 is normal
+    pub(crate) rx: f64, //the random part of the ball's speed across, swapped for another at every point
+
+    pub(crate) score: u16,
+    pub(crate) cpu_score: u16, //balls that got past the player, only decides games in a series
+    pub(crate) win_score: u16,
+    pub(crate) series: Option<Series>,
+    pub(crate) rally: bool,
+    pub(crate) shrink: bool,
+    pub(crate) combo: u16, //player returns since the last miss, only counted in rally scoring
+    pub(crate) meter: ComboMeter, //fills with returns in a row, full it doubles the points for a while
+    pub(crate) impacts: Impacts, //recent hits, for squashing the paddles and ball as they are drawn
+    pub(crate) splits: Option<Splits>, //time attack only
+    pub(crate) targets: Option<Targets>, //target practice only
+    pub(crate) tick_count: u64,
+
+    pub(crate) rng: ChaCha12Rng, //what StdRng wraps, named directly so it can be saved
+    pub(crate) signal: RandomSignal,
+    pub(crate) streamdata: Vec<u64>,
+
+    pub(crate) procedural: bool,
+    pub(crate) level: Option<Level>,
+    pub(crate) boss: Option<Boss>, //on a boss stage of a generated campaign
+    pub(crate) world: World,
+    pub(crate) custom_level: Option<Level>, //layout restored on reset when not procedural
+    pub(crate) editor: Option<Editor>,
+
+    pub(crate) win: bool,
+    pub(crate) endless: bool, //kept playing after a win, the score counting on past the win score
+    pub(crate) celebration: u64, //ticks since the player won, the court holds still meanwhile
+    pub(crate) results: Option<Results>, //what to do next, once the win has been celebrated
+    pub(crate) flipped: bool, //ends swapped by a rematch, the player defends the top of the screen
+    pub(crate) lost: bool,
+    pub(crate) win_time: f64,
+    pub(crate) records: Vec<Record>, //beaten during the current game
+    pub(crate) last_game: Option<Summary>, //kept when a game is left for the menu or restarted
+
+    pub(crate) low_bandwidth: bool,
+    pub(crate) max_fps: u16, //0 leaves drawing uncapped
+    pub(crate) key_releases: bool, //the terminal reports releases, so arrows can be held down
+    pub(crate) held: Option<KeyCode>, //the arrow held down, moving the paddle every tick
+    glide: Option<(KeyCode, f64)>, //the way the paddle is moving under the arrows and how fast
+    pub(crate) tick_scale: u16, //how many ticks' worth of play each tick covers while the game can't keep up
+    pub(crate) zen: bool, //the court takes the whole screen, no gauges
+    pub(crate) locale: Locale,
+    pub(crate) palette: Palette,
+    pub(crate) monochrome: bool,
+    pub(crate) reduced_motion: bool, //for photosensitive players, nothing flashes or moves for show
+    pub(crate) toasts: Toasts,
+
+    pub(crate) events: Vec<GameEvent>, //what happened during the last tick
+    pub(crate) telemetry: Option<Telemetry>,
+    pub(crate) stats: Option<StatsStream>,
+    pub(crate) mods: Mods,
+    #[cfg(feature = "twitch")]
+    pub(crate) chat: Option<crate::chat::Chat>,
+    pub(crate) mod_hud: String,
+    pub(crate) audio: Box<dyn AudioBackend>,
+    pub(crate) audio_offset: u16, //ticks the paddle click runs ahead of the hit
+    pub(crate) early_click: Option<Paddle>, //played ahead, so the hit itself stays quiet
+}
+
+impl App {
+    pub(crate) fn new(config: &Config) -> App {
+        let mut app = App::headless(config);
+        app.audio = sound::open(config.audio.as_deref());
+        app.locale = Locale::load(config.lang.as_deref());
+        app.settings = Settings::load(&app.file(settings_file::SETTINGS_FILE));
+        if let Some(name) = &config.profile {
+            if let Err(err) = app.select_profile(name) {
+                warn!("can't open profile {}: {}", name, err);
+            }
+        } else if app.mode() == Mode::Menu {
+            app.profiles.refresh();
+            app.set_mode(Mode::Profiles);
+        }
+        if let Some(palette) = &config.palette {
+            app.settings.palette = palette.clone();
+        }
+        if let Some(difficulty) = config.difficulty {
+            app.settings.difficulty = difficulty;
+        }
+        if let Some(speed) = config.paddle_speed {
+            app.settings.paddle_speed = speed;
+        }
+        if let Some(acceleration) = config.acceleration {
+            app.settings.acceleration = acceleration;
+        }
+        if let Some(modifiers) = config.modifiers {
+            app.settings.modifiers = modifiers;
+        }
+        app.modifiers = app.settings.modifiers;
+        app.apply_settings();
+        app.mods = Mods::load(Path::new(mods::MODS_DIR));
+        if save::exists(&app) {
+            app.menu.offer_continue();
+        }
+        app
+    }
+
+    // Where a per-player file goes, in the profile's directory once one is picked
+    pub(crate) fn file(&self, name: &str) -> PathBuf {
+        match &self.profile {
+            Some(profile) => profile.dir().join(name),
+            None => PathBuf::from(name),
+        }
+    }
+
+    // Switches to the profile along with its settings and saved game
+    pub(crate) fn select_profile(&mut self, name: &str) -> io::Result<()> {
+        let profile = Profile::open(name)?;
+        info!("playing as {}", profile.name);
+        self.profile = Some(profile);
+        self.settings = Settings::load(&self.file(settings_file::SETTINGS_FILE));
+        self.apply_settings();
+        if self.splits.is_some() {
+            self.splits = Some(Splits::load(&self.file(&splits::file_name(self.win_score))));
+        }
+        self.menu = Menu::new();
+        if save::exists(self) {
+            self.menu.offer_continue();
+        }
+        Ok(())
+    }
+
+    pub(crate) fn headless(config: &Config) -> App {
+        let seed = config.seed.unwrap_or_else(|| rand::thread_rng().gen());
+        let mut rng = ChaCha12Rng::seed_from_u64(seed);
+        let mut signal = RandomSignal::new(0,100, rng.gen());
+        let streamdata = signal.by_ref().take(200).collect::<Vec<u64>>();
+
+        let level = config.procedural.then(|| Level::generate(1, &mut rng));
+        let ball_size = config.ball_size.unwrap_or((5.0, 5.0));
+        let paddle_size = config.paddle_size.unwrap_or((10.0, 3.0));
+        let mut world = World::default();
+        let ball = world.spawn(
+            Kind::Ball,
+            Rectangle {
+                x: 0.0,
+                y: 0.0,
+                width: ball_size.0,
+                height: ball_size.1,
+                color: Color::Red,
+            },
+            Some(Velocity { x: 1.0, y: 1.0 }),
+        );
+        let board = world.spawn(
+            Kind::Paddle(Paddle::Player),
+            Rectangle {
+                x: Court::FULL.left(),
+                y: PLAYER_Y,
+                width: paddle_size.0,
+                height: paddle_size.1,
+                color: Color::White,
+            },
+            None,
+        );
+        let cpu = world.spawn(
+            Kind::Paddle(Paddle::Cpu),
+            Rectangle {
+                x: Court::FULL.left(),
+                y: CPU_TOP - paddle_size.1,
+                width: paddle_size.0,
+                height: paddle_size.1,
+                color: Color::White,
+            },
+            None,
+        );
+        let mut app = App {
+            screens: Navigator::new(if config.screensaver {
+                Mode::Demo
+            } else if config.procedural || config.level.is_some() {
+                Mode::Play
+            } else {
+                Mode::Menu
+            }),
+            help: false,
+            screenshot: false,
+            exit_code: None,
+            confirm_quit: !config.no_confirm_quit,
+            quitting: false,
+            focus_pause: !config.no_focus_pause,
+            paused_by_focus: false,
+            idle_pause: (config.idle_pause.unwrap_or(60) as f64 / TICK.as_secs_f64()).round() as u64,
+            idle_ticks: 0,
+            idle: false,
+            screensaver: config.screensaver,
+            autopilot: false,
+            menu: Menu::new(),
+            profile: None,
+            profiles: ProfileMenu::new(),
+            settings: Settings {
+                modifiers: config.modifiers.unwrap_or_default(),
+                ..Settings::default()
+            },
+            settings_menu: SettingsMenu::new(),
+            debug: Diagnostics::new(),
+            history: History::new(seed, config.args.clone()),
+            replay: None,
+            instant: InstantReplay::new(),
+
+            ball,
+            catchable: false,
+            visibility: Visibility::new(),
+            board,
+            cpu,
+            playground: Court::FULL,
+            paddle_size,
+            practice: config.practice,
+            hardcore: config.hardcore,
+            run_rank: None,
+            modifiers: config.modifiers.unwrap_or_default(),
+            vertical: config.vertical,
+            orientation: if config.sideways {
+                Orientation::Sideways
+            } else {
+                Orientation::Upright
+            },
+            max_speed: config.max_speed.unwrap_or(4.0),
+            slow_motion: config.slow_motion.unwrap_or(0.35),
+            speed: config.speed.unwrap_or(1.0),
+            slow_ticks: 0,
+            rx: 0.0,
+
+            score: 0,
+            cpu_score: 0,
+            win_score: config.win_score.unwrap_or(10),
+            series: config.best_of.map(Series::new),
+            rally: config.rally,
+            targets: config.targets.then(Targets::new),
+            shrink: config.shrink,
+            combo: 0,
+            meter: ComboMeter::default(),
+            impacts: Impacts::default(),
+            splits: config
+                .time_attack
+                .then(|| Splits::load(Path::new(&splits::file_name(config.win_score.unwrap_or(10))))),
+            tick_count: 0,
+
+            rng,
+            signal,
+            streamdata,
+
+            procedural: config.procedural,
+            level,
+            boss: None,
+            world,
+            custom_level: None,
+            editor: None,
+
+            win: false,
+            endless: false,
+            celebration: 0,
+            results: None,
+            flipped: false,
+            lost: false,
+            win_time: 0.0,
+            records: Vec::new(),
+            last_game: None,
+
+            low_bandwidth: config.low_bandwidth,
+            max_fps: config.max_fps.unwrap_or(60),
+            key_releases: false,
+            held: None,
+            glide: None,
+            tick_scale: 1,
+            zen: false,
+            locale: Locale::english(),
+            palette: if config.monochrome {
+                palette::MONOCHROME
+            } else {
+                Palette::named(config.palette.as_deref())
+            },
+            monochrome: config.monochrome,
+            reduced_motion: config.reduced_motion,
+            toasts: Toasts::new(),
+
+            events: Vec::new(),
+            telemetry: config.export_telemetry.as_deref().map(Telemetry::new),
+            stats: None,
+            mods: Mods::none(),
+            #[cfg(feature = "twitch")]
+            chat: None,
+            mod_hud: String::new(),
+            audio: Box::new(Silent),
+            audio_offset: (config.audio_offset.unwrap_or(0) as f64 / TICK_MS).round() as u16,
+            early_click: None,
+        };
+        app.cast_shadow();
+        app
+    }
+
+    pub(crate) fn mode(&self) -> Mode {
+        self.screens.current()
+    }
+
+    pub(crate) fn screen(&self) -> &'static dyn Screen {
+        self.mode().screen()
+    }
+
+    pub(crate) fn set_mode(&mut self, mode: Mode) {
+        self.screens.replace(mode);
+    }
+
+    // A screen over the current one, `back` closes it again
+    pub(crate) fn open(&mut self, mode: Mode) {
+        self.screens.push(mode);
+    }
+
+    pub(crate) fn back(&mut self) {
+        self.screens.back();
+    }
+
+    // Puts the palette and volume from the settings into effect, the other settings are
+    // read where they are used
+    pub(crate) fn apply_settings(&mut self) {
+        if !self.monochrome {
+            self.palette = Palette::named(Some(&self.settings.palette));
+        }
+        self.audio.set_volume(self.settings.volume as f32 / 100.0);
+    }
+
+    pub(crate) fn post_notice(&mut self, notice: String) {
+        self.toasts.post(notice, Instant::now());
+    }
+
+    fn end_game(&mut self, winner: Winner) {
+        self.emit(GameEvent::Win(winner));
+        self.win = winner == Winner::Player;
+        self.lost = winner == Winner::Cpu;
+        self.win_time = self.elapsed();
+        self.celebration = 0;
+        if let Some(series) = &mut self.series {
+            series.record(winner);
+            info!("series {}-{}, best of {}", series.player, series.cpu, series.best_of);
+        }
+        // a win gets its celebration first, a loss goes straight to the choices
+        if self.lost {
+            self.results = Some(Results::new(self.next_game_ready(), false));
+        }
+        if self.autopilot {
+            return; //the cpu played it, so it is nobody's record
+        }
+        if self.hardcore {
+            self.finish_run();
+        } else if let (Some(profile), true) = (&mut self.profile, self.targets.is_none()) {
+            let best = profile.stats.best_time;
+            if let Err(err) = profile.record(winner, self.win_time, self.settings.difficulty.rating()) {
+                warn!("can't update profile {}: {}", profile.name, err);
+            }
+            info!("{} is now rated {:.0}", profile.name, profile.stats.rating);
+            if profile.stats.best_time != best {
+                self.records.push(Record::FastestWin(self.win_time));
+            }
+        }
+        let path = self.file(&splits::file_name(self.win_score));
+        if let (Some(splits), Winner::Player) = (&mut self.splits, winner) {
+            match splits.finish(&path) {
+                Ok(true) => {
+                    info!("personal best of {:.2}s", self.win_time);
+                    self.records.push(Record::PersonalBest(self.win_time));
+                    let notice = self.locale.text("notice.personal_best");
+                    self.post_notice(notice);
+                }
+                Ok(false) => {}
+                Err(err) => warn!("can't write {}: {}", path.display(), err),
+            }
+        }
+    }
+
+    // Puts a finished hardcore run on the leaderboard
+    fn finish_run(&mut self) {
+        let path = Path::new(LEADERBOARD_FILE);
+        let mut board = Leaderboard::load(path);
+        let rank = board.add(Entry {
+            name: self.profile.as_ref().map(|profile| profile.name.clone()),
+            score: self.score,
+            time: self.win_time,
+            seed: self.history.seed,
+            modifiers: self.modifiers.names().into_iter().map(String::from).collect(),
+        });
+        info!("hardcore run placed {} of {} on seed {}", rank.0, rank.1, self.history.seed);
+        if let Err(err) = board.save(path) {
+            warn!("can't write {}: {}", path.display(), err);
+        }
+        self.run_rank = Some(rank);
+        if rank.0 == 1 && rank.1 > 1 {
+            self.records.push(Record::BestRun(self.score));
+            let notice = self.locale.format("notice.best_run", &[&self.history.seed]);
+            self.post_notice(notice);
+        }
+    }
+
+    // Seconds of play so far, counting ticks of TICK
+    pub(crate) fn elapsed(&self) -> f64 {
+        (self.tick_count as f64 * TICK_MS) / 1000.0
+    }
+
+    fn frame(&self) -> Frame {
+        Frame {
+            world: self.world.clone(),
+            catchable: self.catchable,
+        }
+    }
+
+    fn show(&mut self, frame: Frame) {
+        self.world = frame.world;
+        self.catchable = frame.catchable;
+    }
+
+    // The replay borrows the court's world, the game itself doesn't move
+    pub(crate) fn start_instant_replay(&mut self) {
+        info!("instant replay at tick {}", self.tick_count);
+        self.instant.start(self.frame());
+        self.open(Mode::InstantReplay);
+    }
+
+    pub(crate) fn instant_replay_tick(&mut self) {
+        match self.instant.step() {
+            Some(frame) => self.show(frame),
+            None => self.stop_instant_replay(),
+        }
+    }
+
+    pub(crate) fn stop_instant_replay(&mut self) {
+        if let Some(live) = self.instant.stop() {
+            self.show(live);
+        }
+        self.back();
+    }
+
+    // Paddle clicks already played ahead of time are left out, and with an audio offset the
+    // next hit is looked for that many ticks ahead
+    fn play_sounds(&mut self) {
+        let (vx, vy) = self.ball_speed();
+        let speed = vx.hypot(vy) / SQRT_2;
+        let pan = self.pan();
+        let early = self.early_click;
+        let intensity = self.palette.ambience(self.progress().level).intensity;
+        let heard: Vec<GameEvent> = self
+            .events
+            .iter()
+            .filter(|event| !matches!(event, GameEvent::BallHitPaddle { paddle, .. } if Some(*paddle) == early))
+            .copied()
+            .collect();
+        if self.events.iter().any(|event| {
+            matches!(
+                event,
+                GameEvent::BallHitPaddle { .. } | GameEvent::BallHitBrick { .. } | GameEvent::PointScored | GameEvent::LifeLost
+            )
+        }) {
+            self.early_click = None; //the ball has turned, whether the prediction was right or not
+        }
+        sound::play_events(self.audio.as_ref(), &heard, speed, pan, intensity);
+
+        if self.audio_offset == 0 || self.early_click.is_some() || self.mode() != Mode::Play || self.game_over() {
+            return;
+        }
+        if let Some(paddle) = self.predict_hit() {
+            let multiplier = if paddle == Paddle::Player { self.multiplier() } else { 1 };
+            sound::play_events(self.audio.as_ref(), &[GameEvent::BallHitPaddle { paddle, multiplier }], speed, pan, intensity);
+            self.early_click = Some(paddle);
+        }
+    }
+
+    // How far a tick moves everything compared to a plain one, slow motion, the stretch
+    // under load and the speed multiplier all included
+    fn dt(&self) -> f64 {
+        let slow = if self.slow_ticks > 0 { self.slow_motion } else { 1.0 };
+        slow * self.tick_scale as f64 * self.speed
+    }
+
+    // The paddle the ball will reach within audio_offset ticks if both keep going as they
+    // are, walls aside, stepping the way the physics does. None when that can't be told
+    // well enough to click early: in slow motion, which may end on the way, or in a zone,
+    // which changes the ball's pace
+    fn predict_hit(&self) -> Option<Paddle> {
+        let ball = &self.world[self.ball];
+        let in_zone = self.level.as_ref().is_some_and(|level| level.zone_at(ball).is_some());
+        if self.slow_ticks > 0 || in_zone {
+            return None;
+        }
+        let step = self.dt();
+        let velocity = self.ball_velocity();
+        let (right, up) = velocity.heading();
+        let (paddle, distance) = if up {
+            let cpu = &self.world[self.cpu];
+            (cpu, cpu.y - (ball.y + ball.height))
+        } else {
+            let board = &self.world[self.board];
+            (board, ball.y - (board.y + board.height))
+        };
+        let ticks = (distance / (velocity.y.abs() * step).min(self.max_speed)).ceil();
+        if distance <= 0.0 || ticks > self.audio_offset as f64 {
+            return None;
+        }
+        let drift = (velocity.x.abs() * step).min(self.max_speed) * ticks;
+        let x = if right { ball.x + drift } else { ball.x - drift };
+        let hits = collision::overlaps_x(&Rectangle { x, ..ball.clone() }, paddle);
+        hits.then_some(if up { Paddle::Cpu } else { Paddle::Player })
+    }
+
+    // Where the ball is across the screen, -1.0 at the left wall and 1.0 at the right
+    fn pan(&self) -> f64 {
+        let (court, ball) = (self.playground, &self.world[self.ball]);
+        let (x, left, width) = match self.orientation {
+            Orientation::Upright => (ball.x + ball.width / 2.0, court.left(), court.width()),
+            Orientation::Sideways => (ball.y + ball.height / 2.0, court.bottom(), court.height()),
+        };
+        ((x - left) / width * 2.0 - 1.0).clamp(-1.0, 1.0)
+    }
+
+    // Moves the player's paddle for an arrow key, already turned for the court, up to the
+    // walls and the edges of its band but never past them. Returns false for any other key
+    pub(crate) fn move_paddle(&mut self, code: KeyCode, step: f64) -> bool {
+        let board = &mut self.world[self.board];
+        let left = self.playground.left();
+        let right = (self.playground.right() - board.width).max(left);
+        match code {
+            KeyCode::Right => board.x = (board.x + step).min(right),
+            KeyCode::Left => board.x = (board.x - step).max(left),
+            KeyCode::Up if self.vertical => board.y = (board.y + step).min(PLAYER_Y + PLAYER_BAND),
+            KeyCode::Down if self.vertical => board.y = (board.y - step).max(PLAYER_Y),
+            _ => return false,
+        }
+        true
+    }
+
+    // Court units a press of an arrow moves the paddle, and the most a held one does a tick
+    fn paddle_step(&self) -> f64 {
+        self.settings.paddle_speed.clamp(*PADDLE_SPEEDS.start(), *PADDLE_SPEEDS.end()) as f64
+    }
+
+    // A held arrow keeps the paddle going every tick. With acceleration it gets up to speed
+    // over a few ticks and, once let go, slows down the same way
+    fn drive_paddle(&mut self) {
+        let top = self.paddle_step();
+        let ramp = top / self.settings.acceleration.ticks() as f64;
+        self.glide = match (self.held, self.glide) {
+            (Some(code), Some((going, speed))) if going == code => Some((code, (speed + ramp).min(top))),
+            (Some(code), _) => Some((code, ramp)),
+            (None, glide) => glide.map(|(code, speed)| (code, speed - ramp)).filter(|&(_, speed)| speed > 0.0),
+        };
+        if let Some((code, speed)) = self.glide {
+            self.move_paddle(code, speed * self.tick_scale as f64 * self.speed);
+        }
+    }
+
+    // Pressing an arrow moves the paddle a step at once, holding it keeps moving it every tick
+    // until the release. Without releases the terminal's key repeat does the same
+    pub(crate) fn press_arrow(&mut self, code: KeyCode) -> bool {
+        if self.held == Some(code) {
+            return true; //a repeat of the key being held
+        }
+        if self.win {
+            // the court holds still for the celebration, the paddle too
+            return matches!(code, KeyCode::Left | KeyCode::Right) || self.vertical && matches!(code, KeyCode::Up | KeyCode::Down);
+        }
+        if !self.move_paddle(code, self.paddle_step() * self.speed) {
+            return false;
+        }
+        if self.key_releases {
+            self.held = Some(code);
+        }
+        true
+    }
+
+    // Practice mode tweaks, clamped to the same limits as the command line
+    pub(crate) fn resize_paddle(&mut self, by: f64) {
+        let width = (self.paddle_size.0 + by).clamp(*PADDLE_WIDTHS.start(), *PADDLE_WIDTHS.end());
+        self.paddle_size.0 = width;
+        self.world[self.board].width = width;
+        self.world[self.cpu].width = width;
+    }
+
+    pub(crate) fn resize_ball(&mut self, by: f64) {
+        let ball = &mut self.world[self.ball];
+        let size = (ball.width + by).clamp(*BALL_SIZES.start(), *BALL_SIZES.end());
+        ball.width = size;
+        ball.height = size;
+    }
+
+    fn run_mods(&mut self) {
+        if self.mods.is_empty() {
+            return;
+        }
+        let (vx, vy) = self.ball_speed();
+        let mut tuning = Tuning {
+            tick: self.tick_count,
+            score: self.score,
+            vx,
+            vy,
+            max_speed: self.max_speed,
+            paddle_width: self.world[self.board].width,
+            hud: std::mem::take(&mut self.mod_hud),
+        };
+        self.mods.run(&self.events, &mut tuning);
+
+        // whatever a mod asks for still has to fit the court
+        self.max_speed = tuning.max_speed.max(0.1);
+        self.set_ball_speed(tuning.vx.clamp(0.1, self.max_speed), tuning.vy.clamp(0.1, self.max_speed));
+        self.world[self.board].width = tuning.paddle_width.clamp(*PADDLE_WIDTHS.start(), *PADDLE_WIDTHS.end());
+        self.mod_hud = tuning.hud;
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        let ball = &self.world[self.ball];
+        Snapshot {
+            step: self.history.step,
+            tick: self.tick_count,
+            ball: (ball.x, ball.y),
+            dir: self.heading(),
+            velocity: self.ball_speed(),
+            rx: self.rx,
+            board_x: self.world[self.board].x,
+            cpu_x: self.world[self.cpu].x,
+            score: self.score,
+        }
+    }
+
+    fn telemetry_row(&self, split: Option<f64>) -> Row {
+        let (ball, board) = (&self.world[self.ball], &self.world[self.board]);
+        let velocity = self.ball_velocity();
+        Row {
+            tick: self.tick_count,
+            ball_x: ball.x,
+            ball_y: ball.y,
+            vx: velocity.x,
+            vy: velocity.y,
+            paddle_x: board.x,
+            paddle_y: board.y,
+            cpu_x: self.world[self.cpu].x,
+            events: self.events.iter().map(GameEvent::name).collect(),
+            split,
+        }
+    }
+
+    // The whole game goes out in one write, so a failure only costs a notice
+    fn sample(&mut self) {
+        let (time, (vx, vy)) = (self.elapsed(), self.ball_speed());
+        let Some(stats) = &mut self.stats else {
+            return;
+        };
+        let sample = Sample {
+            time,
+            tick: self.tick_count,
+            score: self.score,
+            cpu_score: self.cpu_score,
+            level: LevelProgress::at(self.tick_count).level,
+            speed: vx.hypot(vy),
+            combo: self.combo,
+        };
+        if let Err(err) = stats.write(&sample) {
+            warn!("can't write stats to {}, stopping: {}", stats.path.display(), err);
+            self.stats = None;
+        }
+    }
+
+    fn export_telemetry(&mut self) {
+        let Some(telemetry) = &self.telemetry else {
+            return;
+        };
+        let notice = match telemetry.write() {
+            Ok(()) => self.locale.format("notice.telemetry", &[&telemetry.path.display()]),
+            Err(err) => self.locale.format("notice.telemetry_failed", &[&err]),
+        };
+        info!("{}", notice);
+        self.post_notice(notice);
+    }
+
+    // , and . step the game speed down and up
+    pub(crate) fn change_speed(&mut self, faster: bool) {
+        let step = if faster { SPEED_STEP } else { -SPEED_STEP };
+        self.speed = (self.speed + step).clamp(*SPEEDS.start(), *SPEEDS.end());
+        info!("game speed {:.2}x", self.speed);
+    }
+
+    // Rally scoring pays more per return as the combo grows, up to four points
+    pub(crate) fn multiplier(&self) -> u16 {
+        if self.rally {
+            1 + (self.combo / 4).min(3)
+        } else {
+            1
+        }
+    }
+
+    // Whether the blink modifier has the ball out of sight, it shows up again whenever it
+    // comes close to a wall or a paddle so bounces and returns are never played blind
+    pub(crate) fn ball_hidden(&self) -> bool {
+        if !self.modifiers.blink || self.visibility.visible || self.mode() != Mode::Play || self.game_over() {
+            return false;
+        }
+        let ball = &self.world[self.ball];
+        let near = |rect: &Rectangle| {
+            let gap_x = (rect.x - (ball.x + ball.width)).max(ball.x - (rect.x + rect.width));
+            let gap_y = (rect.y - (ball.y + ball.height)).max(ball.y - (rect.y + rect.height));
+            gap_x < modifiers::BLINK_NEAR && gap_y < modifiers::BLINK_NEAR
+        };
+        let court = self.playground;
+        let by_wall = ball.x - court.left() < modifiers::BLINK_NEAR
+            || court.right() - (ball.x + ball.width) < modifiers::BLINK_NEAR
+            || ball.y - court.bottom() < modifiers::BLINK_NEAR
+            || court.top() - (ball.y + ball.height) < modifiers::BLINK_NEAR;
+        !(by_wall || near(&self.world[self.board]) || near(&self.world[self.cpu]))
+    }
+
+    // Whether the court shows the world's entities of `kind` just now. A blinked out ball
+    // takes its shadow with it, which would give it away otherwise
+    pub(crate) fn shown(&self, kind: Kind) -> bool {
+        match kind {
+            Kind::Ball => !self.ball_hidden(),
+            Kind::Shadow => !self.ball_hidden() && matches!(self.mode(), Mode::Play | Mode::Paused) && !self.game_over(),
+            Kind::Paddle(_) | Kind::Brick(_) | Kind::Boss | Kind::ExtraBall | Kind::Ring => true,
+        }
+    }
+
+    // The shadow modifier's decoy comes with the match, put across the court from the ball
+    pub(crate) fn cast_shadow(&mut self) {
+        self.world.despawn_all(Kind::Shadow);
+        if self.modifiers.shadow {
+            self.world.spawn(Kind::Shadow, self.world[self.ball].clone(), None);
+            self.world.mirror(self.ball, self.playground);
+        }
+    }
+
+    // Moves the side walls in a step, taking the paddles along
+    fn close_walls(&mut self) {
+        self.playground = modifiers::narrowed(self.playground);
+        let (left, right) = (self.playground.left(), self.playground.right());
+        for paddle in [self.board, self.cpu] {
+            let paddle = &mut self.world[paddle];
+            paddle.x = paddle.x.clamp(left, (right - paddle.width).max(left));
+        }
+    }
+
+    pub(crate) fn progress(&self) -> LevelProgress {
+        LevelProgress::at(self.tick_count)
+    }
+
+    pub(crate) fn game_over(&self) -> bool {
+        self.win || self.lost
+    }
+
+    // A game still being played for real, also while an instant replay is watched over it.
+    // A replayed one doesn't count
+    pub(crate) fn in_progress(&self) -> bool {
+        matches!(self.mode(), Mode::Play | Mode::Paused | Mode::InstantReplay) && !self.game_over() && self.replay.is_none()
+    }
+
+    // How the game on the court went, None unless one has been played
+    pub(crate) fn summary(&self) -> Option<Summary> {
+        if !matches!(self.mode(), Mode::Play | Mode::Paused | Mode::InstantReplay) || self.tick_count == 0 {
+            return None;
+        }
+        let mode = if self.practice {
+            GameMode::Practice
+        } else if self.hardcore {
+            GameMode::Hardcore
+        } else if self.targets.is_some() {
+            GameMode::Targets
+        } else if self.splits.is_some() {
+            GameMode::TimeAttack
+        } else if let Some(series) = self.series {
+            GameMode::Series(series.best_of)
+        } else if self.rally {
+            GameMode::Rally
+        } else if self.procedural {
+            GameMode::Procedural
+        } else {
+            GameMode::Classic
+        };
+        let winner = if self.win || self.endless {
+            Some(Winner::Player)
+        } else if self.lost {
+            Some(Winner::Cpu)
+        } else {
+            None
+        };
+        Some(Summary {
+            mode,
+            winner,
+            score: self.score,
+            cpu_score: self.cpu_score,
+            win_score: self.win_score,
+            time: if self.game_over() || self.endless { self.win_time } else { self.elapsed() },
+            level: self.progress().level,
+            records: self.records.clone(),
+            profile: self.profile.as_ref().map(|profile| profile.name.clone()),
+            rating: self.profile.as_ref().filter(|_| self.rated()).map(|profile| profile.stats.rating),
+            modifiers: self.modifiers,
+        })
+    }
+
+    // A ball got past the player
+    fn miss(&mut self) {
+        if self.score > 0 && !self.hardcore && self.targets.is_none() {
+            self.score -= 1;
+        }
+        self.cpu_score += 1;
+        self.combo = 0;
+        self.emit(GameEvent::LifeLost);
+    }
+
+    // Sends the ball back off the face of a brick or the boss it ran into
+    fn bounce(&mut self, side: Side) {
+        match side {
+            Side::Top => self.turn_ball(None, Some(true)),
+            Side::Bottom => self.turn_ball(None, Some(false)),
+            Side::Left => self.turn_ball(Some(false), None),
+            Side::Right => self.turn_ball(Some(true), None),
+        }
+    }
+
+    // The ball's velocity in the world is all there is to which way it goes and how fast
+    pub(crate) fn ball_velocity(&self) -> Velocity {
+        self.world.velocity(self.ball).unwrap()
+    }
+
+    // Whether the ball goes right and whether up
+    pub(crate) fn heading(&self) -> (bool, bool) {
+        self.ball_velocity().heading()
+    }
+
+    // Sends the ball right or left and up or down at the speed it goes, None keeps that way
+    pub(crate) fn turn_ball(&mut self, right: Option<bool>, up: Option<bool>) {
+        let velocity = self.ball_velocity().turned(right, up);
+        self.world.set_velocity(self.ball, velocity);
+    }
+
+    // How fast the ball goes across and along the court, leaving out the random part
+    pub(crate) fn ball_speed(&self) -> (f64, f64) {
+        let velocity = self.ball_velocity();
+        (velocity.x.abs() - self.rx, velocity.y.abs())
+    }
+
+    // Speeds the ball up or slows it down, the way it is going and its random part kept
+    pub(crate) fn set_ball_speed(&mut self, vx: f64, vy: f64) {
+        let (right, up) = self.heading();
+        let velocity = Velocity { x: vx + self.rx, y: vy }.turned(Some(right), Some(up));
+        self.world.set_velocity(self.ball, velocity);
+    }
+
+    // Speeds the ball up by so much across and along, up to the cap, and returns how fast it goes
+    fn speed_up(&mut self, x: f64, y: f64) -> (f64, f64) {
+        let (vx, vy) = self.ball_speed();
+        let (vx, vy) = ((vx + x).min(self.max_speed), (vy + y).min(self.max_speed));
+        self.set_ball_speed(vx, vy);
+        (vx, vy)
+    }
+
+    // A new random part of the ball's speed across, for the next point
+    fn randomize_ball(&mut self) {
+        let (vx, vy) = self.ball_speed();
+        self.rx = x_randomize(&mut self.signal);
+        self.set_ball_speed(vx, vy);
+    }
+
+    // A stage is won, by its last brick or by beating its boss
+    fn stage_cleared(&mut self, index: u32) {
+        self.emit(GameEvent::StageCleared(index));
+        if self.modifiers.walls && self.playground != Court::FULL {
+            info!("stage {} cleared, the walls go back out", index);
+            self.playground = Court::FULL;
+        }
+    }
+
+    // Moves a generated campaign on to the stage, every few stages is a boss instead of bricks
+    fn next_stage(&mut self, index: u32) {
+        if boss::is_boss_stage(index) {
+            info!("stage {} is a boss stage", index);
+            self.set_level(Some(Level { index, ..Level::empty() }));
+            self.boss = Some(Boss::new(index));
+            self.world.despawn_all(Kind::ExtraBall);
+            let (body, velocity) = boss::body(self.playground);
+            self.world.spawn(Kind::Boss, body, Some(velocity));
+        } else {
+            let level = Level::generate(index, &mut self.rng);
+            self.set_level(Some(level));
+        }
+    }
+
+    // Puts the stage's bricks on the court in place of the last one's
+    pub(crate) fn set_level(&mut self, mut level: Option<Level>) {
+        level::clear(&mut self.world);
+        if let Some(level) = &mut level {
+            level.lay_out(&mut self.world);
+        }
+        self.level = level;
+    }
+
+    // Whether the game moves the profile's rating, the modes with their own scoring don't
+    fn rated(&self) -> bool {
+        !self.practice && !self.hardcore && self.targets.is_none() && !self.endless
+    }
+
+    // Quitting a game in progress counts as losing it, so leaving can't save a rating
+    pub(crate) fn abandon(&mut self) {
+        if !self.in_progress() || !self.rated() {
+            return;
+        }
+        info!("game abandoned at tick {}", self.tick_count);
+        let time = self.elapsed();
+        if let Some(profile) = &mut self.profile {
+            if let Err(err) = profile.record(Winner::Cpu, time, self.settings.difficulty.rating()) {
+                warn!("can't update profile {}: {}", profile.name, err);
+            }
+            info!("{} is now rated {:.0}", profile.name, profile.stats.rating);
+        }
+    }
+
+    // A series game has finished and the series is still open
+    pub(crate) fn next_game_ready(&self) -> bool {
+        self.game_over() && self.series.is_some_and(|series| series.winner().is_none())
+    }
+
+    // A plain game won, which can go on as endless play
+    pub(crate) fn can_keep_playing(&self) -> bool {
+        self.win && self.mode() == Mode::Play && self.series.is_none() && !self.hardcore && self.targets.is_none()
+    }
+
+    // Picks the game up again after a win with the speed and score it had, the win is
+    // already on the profile and nothing after it can lose it
+    pub(crate) fn keep_playing(&mut self) {
+        info!("endless play from {} points at tick {}", self.score, self.tick_count);
+        self.win = false;
+        self.endless = true;
+        self.results = None;
+    }
+
+    // Starts the match over from nothing, a whole series again if it was one, with the
+    // player at the other end of the screen. The cpu always takes a rematch
+    pub(crate) fn rematch(&mut self) {
+        reset(self);
+        self.flipped = !self.flipped;
+        serve(self);
+        info!("rematch with the ends swapped, the player at the {}", if self.flipped { "top" } else { "bottom" });
+    }
+
+    // A won game stands still while the banner dances and the music plays, after which
+    // the results come up
+    fn celebrate(&mut self) {
+        let scale = self.tick_scale as u64;
+        self.celebration += scale;
+        if self.celebration % 16 < scale {
+            let value = self.signal.next().unwrap();
+            self.streamdata.pop();
+            self.streamdata.insert(0, value);
+        }
+        if self.results.is_none() && self.celebration >= CELEBRATION_TICKS && self.mode() == Mode::Play {
+            self.results = Some(Results::new(self.next_game_ready(), self.can_keep_playing()));
+        }
+    }
+
+    // Only real play makes events, the demo and free play after a game ends don't count
+    fn emit(&mut self, event: GameEvent) {
+        if !self.game_over() && self.mode() != Mode::Demo {
+            self.events.push(event);
+        }
+    }
+
+    pub(crate) fn on_tick(&mut self) {
+        self.events.clear();
+        if self.win {
+            self.celebrate();
+            return;
+        }
+        let mut split = None;
+        if self.mode() == Mode::Play {
+            self.drive_paddle();
+        }
+        // every move this tick is scaled by dt, so a slow motion tick covers less ground and
+        // a longer tick under load covers more, counters below advance by the scale to match.
+        // The speed multiplier only scales the moves, the clock keeps counting ticks
+        let scale = self.tick_scale as u64;
+        let dt = self.dt();
+        self.slow_ticks = self.slow_ticks.saturating_sub(self.tick_scale);
+
+        // the ball stops on an edge it would have gone past, so it only counts as reaching it
+        // heading that way, not while it is leaving again
+        let (right, up) = self.heading();
+        if let Some(right) = collision::reflect_x(&mut self.world[self.ball], self.playground).filter(|&now| now != right) {
+            let ball = &self.world[self.ball];
+            debug!("ball hit side wall at ({:.1}, {:.1})", ball.x, ball.y);
+            self.turn_ball(Some(right), None);
+            self.emit(GameEvent::BallHitWall);
+        }
+
+        match collision::reflect_y(&mut self.world[self.ball], self.playground).filter(|&now| now != up) {
+            Some(true) => {
+                self.turn_ball(None, Some(true));
+                self.randomize_ball();
+                self.miss();
+                debug!("ball passed the player, score {}", self.score);
+            }
+            Some(false) => {
+                self.turn_ball(None, Some(false));
+                self.randomize_ball();
+                if self.targets.is_none() {
+                    self.score += self.meter.multiplier();
+                }
+                debug!("ball passed the cpu, score {}", self.score);
+                self.emit(GameEvent::PointScored);
+                let time = self.elapsed();
+                if let (Some(splits), Mode::Play, false) = (&mut self.splits, self.screens.current(), self.win || self.lost) {
+                    split = splits.record(self.score, time);
+                }
+            }
+            None => {}
+        }
+
+        let ball = self.world[self.ball].clone();
+        let (right, up) = self.heading();
+        if up && ball.y > self.playground.along(CPU_WAKES) {
+            let speed = (4.0 + self.rx) * self.settings.difficulty.speed() * dt;
+            ai::follow(&mut self.world[self.cpu], &ball, right, speed, self.playground, &mut self.rng);
+        }
+        if (self.mode() == Mode::Demo || self.autopilot) && !up && ball.y < self.playground.along(AUTOPILOT_WAKES) {
+            ai::follow(&mut self.world[self.board], &ball, right, (4.0 + self.rx) * dt, self.playground, &mut self.rng);
+        }
+
+        if collision::overlaps(&ball, &self.world[self.cpu]) {
+            if self.heading().1 {
+                debug!("ball hit cpu paddle at x {:.1}", ball.x);
+                self.emit(GameEvent::BallHitPaddle { paddle: Paddle::Cpu, multiplier: 1 });
+            }
+            self.turn_ball(None, Some(false));
+        }
+
+        let board = self.world[self.board].clone();
+        let paddle_top = board.y + board.height;
+        if collision::overlaps_x(&ball, &board) {
+            if ball.y < paddle_top + self.playground.height() * CATCH_REACH {
+                self.catchable = true;
+            }
+
+            // only a ball overlapping the paddle itself is returned, wherever the paddle sits
+            if collision::overlaps(&ball, &board) {
+                if !self.heading().1 {
+                    debug!("ball hit player paddle at x {:.1}", ball.x);
+                    if self.rally && self.mode() != Mode::Demo && !self.game_over() {
+                        self.combo += 1;
+                        self.score += self.multiplier() * self.meter.multiplier();
+                        debug!("rally combo {}, score {}", self.combo, self.score);
+                    }
+                    let multiplier = self.multiplier();
+                    self.emit(GameEvent::BallHitPaddle { paddle: Paddle::Player, multiplier });
+                }
+                self.turn_ball(None, Some(true));
+            }
+        } else {
+            self.catchable = false;
+            let gap = (board.x - (ball.x + ball.width)).max(ball.x - (board.x + board.width));
+            let near_miss = !self.heading().1 && gap < NEAR_MISS && ball.y < paddle_top + self.playground.height() * NEAR_MISS_REACH && ball.y > board.y;
+            if near_miss && self.slow_ticks == 0 && self.slow_motion < 1.0 && self.mode() == Mode::Play && !self.game_over() {
+                debug!("near miss by {:.1}, slowing down", gap);
+                self.slow_ticks = SLOW_TICKS;
+            }
+        }
+
+        // everything with a velocity, the ball too, moves along together, faster or slower
+        // through the level's zones
+        let level = &self.level;
+        self.world.movement(dt, self.max_speed, |body| {
+            let zone = level.as_ref().and_then(|level| level.zone_at(body));
+            (zone.map_or(1.0, ZoneKind::pace), zone.map_or(0.0, ZoneKind::lift))
+        });
+        self.world.bounce_off_walls(Kind::Boss, self.playground);
+        self.world.bounce_off_walls(Kind::ExtraBall, self.playground);
+
+        // a fast ball stops on an edge instead of going past it, so it never shows outside the
+        // court, and the wall and scoring checks catch it there on the next tick
+        let (court, ball) = (self.playground, &mut self.world[self.ball]);
+        ball.x = ball.x.clamp(court.left(), (court.right() - ball.width).max(court.left()));
+        ball.y = ball.y.clamp(court.bottom(), (court.top() - ball.height).max(court.bottom()));
+
+        if let Some(level) = &self.level {
+            let ball = self.world[self.ball].clone();
+            let hit = level::hit(&mut self.world, &ball);
+            let broken = matches!(hit, Some((_, true)));
+            let standing = self.world.count(Kind::Brick(BrickKind::Breakable));
+            let cleared = (standing == 0 && self.boss.is_none()).then_some(level.index);
+            if let Some((side, broken)) = hit {
+                self.bounce(side);
+                self.emit(GameEvent::BallHitBrick { broken });
+            }
+            if let (Some(index), true) = (cleared, broken) {
+                self.stage_cleared(index);
+            }
+            if let (Some(index), true) = (cleared, self.procedural) {
+                info!("stage {} cleared, generating stage {}", index, index + 1);
+                self.next_stage(index + 1);
+            }
+        }
+
+        let body = self.world.of(Kind::Boss).next().cloned();
+        match (self.boss.as_mut().and_then(|boss| boss.update(scale, &mut self.rng)), &body) {
+            (Some(Attack::ExtraBall), Some(body)) => {
+                let (extra, velocity) = boss::extra_ball(body, &mut self.rng);
+                self.world.spawn(Kind::ExtraBall, extra, Some(velocity));
+            }
+            (Some(Attack::SpeedUp), _) => {
+                let (vx, vy) = self.speed_up(BOSS_SPEED_UP, BOSS_SPEED_UP);
+                info!("the boss speeds the ball up to vx {:.1} vy {:.1}", vx, vy);
+            }
+            _ => {}
+        }
+        if let (Some(boss), Some(body)) = (&mut self.boss, &body) {
+            let side = boss.hit(body, &self.world[self.ball]);
+            let (board, playground) = (self.world[self.board].clone(), self.playground);
+            let landed = self.world.collide(Kind::ExtraBall, |extra| boss::landing(extra, &board, playground));
+            boss.take_returns(&landed);
+            let health = boss.health;
+            let hit = side.is_some();
+            if let Some(side) = side {
+                self.bounce(side);
+            }
+            for landed in landed.iter().chain(hit.then_some(&Landed::Returned)) {
+                match landed {
+                    Landed::Returned => self.emit(GameEvent::BossHit(health)),
+                    Landed::Missed => self.miss(),
+                }
+            }
+            if health == 0 {
+                let index = self.level.as_ref().map_or(1, |level| level.index);
+                info!("boss of stage {} beaten", index);
+                self.boss = None;
+                self.world.despawn_all(Kind::ExtraBall);
+                self.world.despawn_all(Kind::Boss);
+                self.stage_cleared(index);
+                if self.procedural {
+                    self.next_stage(index + 1);
+                }
+            }
+        }
+
+        self.world.expire(self.tick_count);
+        let rising = self.heading().1;
+        if let (Some(targets), Mode::Play, false) = (&mut self.targets, self.screens.current(), self.win || self.lost) {
+            let hits = targets.update(self.tick_count, &mut self.world, self.ball, rising, self.playground, &mut self.rng);
+            if hits > 0 {
+                self.score += hits * self.meter.multiplier();
+                debug!("ball went through a target, score {}", self.score);
+                self.emit(GameEvent::TargetHit);
+            }
+        }
+
+        self.tick_count += scale;
+
+        if self.tick_count % LEVEL_TICKS < scale {
+            let (vx, vy) = self.speed_up(0.2, 0.1);
+            info!("speed up to vx {:.1} vy {:.1}", vx, vy);
+            self.emit(GameEvent::LevelUp(self.progress().level));
+            if self.modifiers.walls && self.mode() != Mode::Demo {
+                self.close_walls();
+                info!("walls close in, the court is {} wide", self.playground.width());
+            }
+            let board = &mut self.world[self.board];
+            if self.shrink && board.width > MIN_PADDLE_WIDTH {
+                board.width = (board.width - 1.0).max(MIN_PADDLE_WIDTH);
+                info!("paddle shrinks to {:.0}", board.width);
+            }
+        }
+
+        if self.game_over() && self.tick_count % 16 < scale {
+            let value = self.signal.next().unwrap();
+            self.streamdata.pop();
+            self.streamdata.insert(0, value);
+        }
+
+        let playing = self.mode() == Mode::Play && !self.game_over();
+        if playing && self.modifiers.blink {
+            self.visibility.tick(scale);
+        }
+        if playing {
+            self.idle_ticks += scale;
+            if self.idle_pause > 0 && self.idle_ticks >= self.idle_pause {
+                info!("no input for {} ticks, pausing", self.idle_ticks);
+                self.idle = true;
+                self.set_mode(Mode::Paused);
+            }
+        }
+        self.impacts.feed(&self.events, self.tick_count);
+        if self.meter.feed(&self.events, scale) {
+            info!("combo meter full, double points for a while");
+            self.emit(GameEvent::ComboFull);
+        }
+
+        if self.mode() == Mode::Demo {
+            if self.score >= self.win_score {
+                reset(self);
+                if self.screensaver && !self.monochrome {
+                    let name = palette::PALETTES[self.rng.gen_range(0..palette::PALETTES.len())];
+                    debug!("screensaver palette {}", name);
+                    self.palette = Palette::named(Some(name));
+                }
+            }
+        } else if !self.game_over() && !self.practice && !self.endless {
+            if self.hardcore {
+                if self.events.iter().any(|event| matches!(event, GameEvent::LifeLost)) {
+                    info!("hardcore run over after {} ticks with {} points", self.tick_count, self.score);
+                    self.end_game(Winner::Cpu);
+                }
+            } else if self.targets.is_some() {
+                if Targets::time_left(self.tick_count) == 0 {
+                    info!("target practice over with {} points", self.score);
+                    self.end_game(if self.score >= self.win_score { Winner::Player } else { Winner::Cpu });
+                }
+            } else if self.score >= self.win_score {
+                info!("player won after {} ticks", self.tick_count);
+                self.end_game(Winner::Player);
+            } else if self.series.is_some() && self.cpu_score >= self.win_score {
+                info!("cpu won after {} ticks", self.tick_count);
+                self.end_game(Winner::Cpu);
+            }
+        }
+
+        if playing {
+            let frame = self.frame();
+            self.instant.record(frame);
+            if self.events.iter().any(|event| matches!(event, GameEvent::PointScored | GameEvent::LifeLost)) {
+                self.instant.offer(self.tick_count);
+            }
+            let row = self.telemetry_row(split);
+            if let Some(telemetry) = &mut self.telemetry {
+                telemetry.record(row);
+            }
+            if self.tick_count % SECOND_TICKS < scale {
+                self.sample();
+            }
+            if self.game_over() {
+                self.export_telemetry();
+            }
+        }
+
+        self.play_sounds();
+        self.run_mods();
+
+        self.world.mirror(self.ball, self.playground);
+        let snapshot = self.snapshot();
+        self.history.record_state(snapshot);
+    }
+}
+
+fn x_randomize(signal: &mut RandomSignal) -> f64{
+    match signal.next().unwrap(){  
+        66.. => 0.1,
+        33.. => -0.1,
+        _ => 0.0
+    }
+}
+
+pub fn serve(game: &mut App) {
+    // anywhere in the left half and a bit, clear of the cpu's end
+    let court = game.playground;
+    game.world[game.ball].x = game.rng.gen_range(court.left()..court.across(8.0 / 15.0));
+    game.world[game.ball].y = game.rng.gen_range(court.bottom()..court.along(0.9));
+    game.world.mirror(game.ball, court);
+}
+
+pub fn reset(game: &mut App) {
+    if let Some(summary) = game.summary() {
+        game.last_game = Some(summary);
+    }
+    game.records.clear();
+    game.modifiers = game.settings.modifiers;
+    game.playground = Court::FULL;
+    game.visibility = Visibility::new();
+    game.rx = 0.0;
+    game.set_ball_speed(1.0, 1.0);
+    game.score = 0;
+    game.cpu_score = 0;
+    game.world[game.board].width = game.paddle_size.0;
+    game.world[game.board].y = PLAYER_Y;
+    game.glide = None;
+    game.combo = 0;
+    game.meter = ComboMeter::default();
+    game.impacts = Impacts::default();
+    if let Some(splits) = &mut game.splits {
+        splits.clear();
+    }
+    if let Some(targets) = &mut game.targets {
+        targets.clear();
+    }
+    game.series = game.series.map(|series| Series::new(series.best_of));
+    game.tick_count = 0;
+    game.slow_ticks = 0;
+    game.early_click = None;
+    game.instant.clear();
+    game.win = false;
+    game.endless = false;
+    game.celebration = 0;
+    game.results = None;
+    game.lost = false;
+    game.win_time = 0.0;
+    game.run_rank = None;
+    if let Some(telemetry) = &mut game.telemetry {
+        telemetry.clear();
+    }
+    game.boss = None;
+    game.world.clear();
+    game.cast_shadow();
+    let level = if game.procedural {
+        Some(Level::generate(1, &mut game.rng))
+    } else {
+        game.custom_level.clone()
+    };
+    game.set_level(level);
+}
+
+// Starts the next game of a series, keeping the games won so far
+pub fn next_game(game: &mut App) {
+    let series = game.series;
+    reset(game);
+    game.series = series;
+}
@@ -0,0 +1,130 @@
+use rand::Rng;
+use ratatui::{style::Color, widgets::canvas::Rectangle};
+
+use crate::{
+    collision::{overlaps, side_of, Side},
+    court::Court,
+    world::Velocity,
+};
+
+const BOSS_EVERY: u32 = 5; //every fifth generated stage is a boss
+const WIDTH: f64 = 40.0;
+const HEIGHT: f64 = 5.0;
+const Y: f64 = 92.0; //under the cpu paddle, where the top rows of bricks would be
+const STRIDE: f64 = 0.8; //court units a tick it moves sideways
+const ATTACK_EVERY: u64 = 200;
+const HIT_COOLDOWN: u64 = 10; //ticks after a hit before the ball can hit again, while it bounces clear
+const EXTRA_SIZE: f64 = 4.0;
+const EXTRA_SPEED: (f64, f64) = (0.6, 1.2);
+
+pub fn is_boss_stage(index: u32) -> bool {
+    index.is_multiple_of(BOSS_EVERY)
+}
+
+// What the boss does when its next attack comes round
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Attack {
+    ExtraBall, //drops a ball of its own at the player
+    SpeedUp,   //the ball in play gets faster
+}
+
+// Where an extra ball ended up this tick
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Landed {
+    Returned, //caught on the player's paddle, which knocks it back into the boss
+    Missed,   //got past the player
+}
+
+// A big paddle across the upper court on a boss stage, sweeping from wall to wall and
+// blocking shots at the cpu. Every hit with the ball costs it a point of health and the
+// stage is cleared once it has none left. Its body is the world's, which moves it along
+// and turns it at the walls
+pub struct Boss {
+    pub health: u16,
+    pub max_health: u16,
+    age: u64, //ticks since the stage began
+    next_attack: u64,
+    last_hit: Option<u64>,
+}
+
+// Where the boss comes in, across the middle of the court and heading right
+pub fn body(court: Court) -> (Rectangle, Velocity) {
+    let rect = Rectangle {
+        x: court.left() + (court.width() - WIDTH) / 2.0,
+        y: Y,
+        width: WIDTH,
+        height: HEIGHT,
+        color: Color::Reset,
+    };
+    (rect, Velocity { x: STRIDE, y: 0.0 })
+}
+
+// The ball an ExtraBall attack drops from under the middle of the boss, falling towards
+// the player and drifting off to either side
+pub fn extra_ball<R: Rng>(boss: &Rectangle, rng: &mut R) -> (Rectangle, Velocity) {
+    let rect = Rectangle {
+        x: boss.x + (boss.width - EXTRA_SIZE) / 2.0,
+        y: boss.y - EXTRA_SIZE,
+        width: EXTRA_SIZE,
+        height: EXTRA_SIZE,
+        color: Color::Reset,
+    };
+    let x = if rng.gen_bool(0.5) { EXTRA_SPEED.0 } else { -EXTRA_SPEED.0 };
+    (rect, Velocity { x, y: -EXTRA_SPEED.1 })
+}
+
+impl Boss {
+    pub fn new(index: u32) -> Boss {
+        let max_health = 3 + 2 * (index / BOSS_EVERY) as u16;
+        Boss {
+            health: max_health,
+            max_health,
+            age: 0,
+            next_attack: ATTACK_EVERY,
+            last_hit: None,
+        }
+    }
+
+    pub fn defeated(&self) -> bool {
+        self.health == 0
+    }
+
+    // Attacks when it is time, the extra ball and the ball speeding up are left to the caller
+    pub fn update<R: Rng>(&mut self, scale: u64, rng: &mut R) -> Option<Attack> {
+        self.age += scale;
+        if self.age < self.next_attack {
+            return None;
+        }
+        self.next_attack = self.age + ATTACK_EVERY;
+        Some(if rng.gen_bool(0.5) { Attack::ExtraBall } else { Attack::SpeedUp })
+    }
+
+    // The face of the boss's `body` the ball ran into, which costs it a point of health
+    pub fn hit(&mut self, body: &Rectangle, ball: &Rectangle) -> Option<Side> {
+        let cooling = self.last_hit.is_some_and(|at| self.age < at + HIT_COOLDOWN);
+        if self.defeated() || cooling || !overlaps(body, ball) {
+            return None;
+        }
+        self.health -= 1;
+        self.last_hit = Some(self.age);
+        Some(side_of(body, ball))
+    }
+
+    // A returned extra ball costs the boss a point of health
+    pub fn take_returns(&mut self, landed: &[Landed]) {
+        let returned = landed.iter().filter(|landed| **landed == Landed::Returned).count() as u16;
+        self.health = self.health.saturating_sub(returned);
+    }
+}
+
+// Whether an extra ball reached the player's paddle or went past the bottom of the court,
+// None while it is still on its way
+pub fn landing(extra: &Rectangle, paddle: &Rectangle, court: Court) -> Option<Landed> {
+    if overlaps(extra, paddle) {
+        Some(Landed::Returned)
+    } else if extra.y + extra.height < court.bottom() {
+        Some(Landed::Missed)
+    } else {
+        None
+    }
+}
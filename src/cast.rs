@@ -0,0 +1,106 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::Path,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use log::{info, warn};
+use serde_json::json;
+
+// An asciinema v2 recording: a JSON header, then one [seconds, "o", text] line for every
+// frame and an "r" line whenever the terminal changes size
+pub struct Cast {
+    file: io::BufWriter<fs::File>,
+    started: Instant,
+    size: (u16, u16),
+}
+
+impl Cast {
+    pub fn create(path: &Path) -> io::Result<Cast> {
+        let size = crossterm::terminal::size()?;
+        let mut file = io::BufWriter::new(fs::File::create(path)?);
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_secs()).unwrap_or(0);
+        let header = json!({
+            "version": 2,
+            "width": size.0,
+            "height": size.1,
+            "timestamp": timestamp,
+            "title": "pong_terminal",
+        });
+        writeln!(file, "{}", header)?;
+        info!("recording to {}", path.display());
+        Ok(Cast {
+            file,
+            started: Instant::now(),
+            size,
+        })
+    }
+
+    fn event(&mut self, kind: &str, data: &str) -> io::Result<()> {
+        let time = self.started.elapsed().as_secs_f64();
+        writeln!(self.file, "{}", json!([(time * 1e6).round() / 1e6, kind, data]))
+    }
+
+    fn frame(&mut self, output: &str) -> io::Result<()> {
+        if let Ok(size) = crossterm::terminal::size() {
+            if size != self.size {
+                self.size = size;
+                self.event("r", &format!("{}x{}", size.0, size.1))?;
+            }
+        }
+        self.event("o", output)?;
+        self.file.flush()
+    }
+}
+
+// Passes the game's output on to the terminal, copying every flushed frame into a cast
+// while one is being recorded
+pub struct Recorded<W> {
+    writer: W,
+    cast: Option<Cast>,
+    pending: Vec<u8>,
+}
+
+impl<W: Write> Recorded<W> {
+    pub fn new(writer: W, cast: Option<Cast>) -> Recorded<W> {
+        Recorded {
+            writer,
+            cast,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl<W: Write> Write for Recorded<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.writer.write(buf)?;
+        if self.cast.is_some() {
+            self.pending.extend_from_slice(&buf[..n]);
+        }
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()?;
+        let Some(cast) = &mut self.cast else {
+            return Ok(());
+        };
+        // a character cut in half waits for the rest of it in the next frame
+        let whole = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(_) => self.pending.len(),
+        };
+        if whole == 0 {
+            return Ok(());
+        }
+        let output = String::from_utf8_lossy(&self.pending[..whole]).into_owned();
+        self.pending.drain(..whole);
+        if let Err(err) = cast.frame(&output) {
+            warn!("can't write the cast, recording stops: {}", err);
+            self.cast = None;
+        }
+        Ok(())
+    }
+}
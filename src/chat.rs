@@ -0,0 +1,102 @@
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::TcpStream,
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+};
+
+use crossterm::event::KeyCode;
+use log::{info, warn};
+
+pub const TWITCH_SERVER: &str = "irc.chat.twitch.tv:6667";
+
+const WINDOW_TICKS: u32 = 8; //votes are counted over about 200ms
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Vote {
+    Left,
+    Right,
+}
+
+// Viewers steer the paddle by typing left or right in a channel, the majority of each
+// window turns into one key press
+pub struct Chat {
+    votes: Receiver<Vote>,
+    left: u32,
+    right: u32,
+    ticks: u32,
+}
+
+impl Chat {
+    // Joins the channel anonymously and reads it on its own thread
+    pub fn connect(server: &str, channel: &str) -> io::Result<Chat> {
+        let channel = channel.trim_start_matches('#').to_lowercase();
+        let mut stream = TcpStream::connect(server)?;
+        let nick = format!("justinfan{}", rand::random::<u32>() % 100_000);
+        write!(stream, "NICK {}\r\nUSER {} 0 * :pong_terminal\r\nJOIN #{}\r\n", nick, nick, channel)?;
+        info!("joined #{} on {} as {}", channel, server, nick);
+
+        let (tx, votes) = mpsc::channel();
+        let reader = BufReader::new(stream.try_clone()?);
+        thread::spawn(move || {
+            if let Err(err) = read_chat(reader, stream, tx) {
+                warn!("chat connection lost: {}", err);
+            }
+        });
+
+        Ok(Chat {
+            votes,
+            left: 0,
+            right: 0,
+            ticks: 0,
+        })
+    }
+
+    // Called once per game tick, returns the winning direction when a window closes
+    pub fn poll(&mut self) -> Option<KeyCode> {
+        for vote in self.votes.try_iter() {
+            match vote {
+                Vote::Left => self.left += 1,
+                Vote::Right => self.right += 1,
+            }
+        }
+
+        self.ticks += 1;
+        if self.ticks < WINDOW_TICKS {
+            return None;
+        }
+        let key = match self.left.cmp(&self.right) {
+            std::cmp::Ordering::Greater => Some(KeyCode::Left),
+            std::cmp::Ordering::Less => Some(KeyCode::Right),
+            std::cmp::Ordering::Equal => None,
+        };
+        self.left = 0;
+        self.right = 0;
+        self.ticks = 0;
+        key
+    }
+}
+
+fn read_chat(reader: BufReader<TcpStream>, mut stream: TcpStream, tx: Sender<Vote>) -> io::Result<()> {
+    for line in reader.lines() {
+        let line = line?;
+        if let Some(server) = line.strip_prefix("PING ") {
+            write!(stream, "PONG {}\r\n", server)?;
+            continue;
+        }
+        // :nick!user@host PRIVMSG #channel :message
+        let message = line
+            .split_once(" PRIVMSG ")
+            .and_then(|(_, rest)| rest.split_once(" :"))
+            .map(|(_, message)| message.trim().to_lowercase());
+        let vote = match message.as_deref() {
+            Some("left" | "l" | "<") => Vote::Left,
+            Some("right" | "r" | ">") => Vote::Right,
+            _ => continue,
+        };
+        if tx.send(vote).is_err() {
+            break; //the game has gone
+        }
+    }
+    Ok(())
+}
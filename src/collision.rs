@@ -0,0 +1,62 @@
+use ratatui::widgets::canvas::Rectangle;
+
+use crate::court::Court;
+
+// Face of a rectangle the ball ran into
+pub enum Side {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+pub fn overlaps(a: &Rectangle, b: &Rectangle) -> bool {
+    overlaps_x(a, b) && a.y < b.y + b.height && b.y < a.y + a.height
+}
+
+// Whether two rectangles share some of the court's width, wherever they are along it
+pub fn overlaps_x(a: &Rectangle, b: &Rectangle) -> bool {
+    a.x < b.x + b.width && b.x < a.x + a.width
+}
+
+// Picks the face with the shallower overlap, which is the one the ball came through
+pub fn side_of(target: &Rectangle, ball: &Rectangle) -> Side {
+    let overlap_x = (target.x + target.width).min(ball.x + ball.width) - target.x.max(ball.x);
+    let overlap_y = (target.y + target.height).min(ball.y + ball.height) - target.y.max(ball.y);
+
+    if overlap_x < overlap_y {
+        if ball.x + ball.width / 2.0 < target.x + target.width / 2.0 {
+            Side::Left
+        } else {
+            Side::Right
+        }
+    } else if ball.y + ball.height / 2.0 < target.y + target.height / 2.0 {
+        Side::Bottom
+    } else {
+        Side::Top
+    }
+}
+
+// Bounces the ball off the side walls. Returns whether it now travels right
+pub fn reflect_x(ball: &mut Rectangle, court: Court) -> Option<bool> {
+    reflect(&mut ball.x, ball.width, court.left(), court.right())
+}
+
+// Bounces the ball off the court ends. Returns whether it now travels up
+pub fn reflect_y(ball: &mut Rectangle, court: Court) -> Option<bool> {
+    reflect(&mut ball.y, ball.height, court.bottom(), court.top())
+}
+
+// Mirrors a position that reached either bound back inside and sets the direction
+// outright, so a ball still touching an edge on the next tick can't flip back
+fn reflect(pos: &mut f64, size: f64, low: f64, high: f64) -> Option<bool> {
+    if *pos <= low {
+        *pos = (2.0 * low - *pos).min(high - size);
+        Some(true)
+    } else if *pos + size >= high {
+        *pos = (2.0 * (high - size) - *pos).max(low);
+        Some(false)
+    } else {
+        None
+    }
+}
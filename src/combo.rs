@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+use crate::events::{GameEvent, Paddle};
+
+const RETURNS: f64 = 6.0; //returns in a row that fill the meter
+const DECAY: f64 = 1.0 / 1200.0; //charge lost a tick, a return's worth every 200 ticks
+const BOOST_TICKS: u64 = 500; //how long a full meter pays out
+const MULTIPLIER: u16 = 2;
+
+// Fills with the player's returns and drains slowly between them. Once full it empties
+// itself into a spell of double points, and a miss loses the lot
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct ComboMeter {
+    pub charge: f64, //0 to 1
+    pub boost: u64, //ticks of the multiplier left
+}
+
+impl ComboMeter {
+    // Brings the meter up to date with a tick's events, true when they fill it
+    pub fn feed(&mut self, events: &[GameEvent], scale: u64) -> bool {
+        if self.charged() {
+            self.boost = self.boost.saturating_sub(scale);
+        } else {
+            self.charge = (self.charge - DECAY * scale as f64).max(0.0);
+        }
+        let mut filled = false;
+        for event in events {
+            match event {
+                GameEvent::BallHitPaddle { paddle: Paddle::Player, .. } if !self.charged() => {
+                    self.charge += 1.0 / RETURNS;
+                    if self.charge >= 1.0 - f64::EPSILON {
+                        self.charge = 0.0;
+                        self.boost = BOOST_TICKS;
+                        filled = true;
+                    }
+                }
+                GameEvent::LifeLost => *self = ComboMeter::default(),
+                _ => {}
+            }
+        }
+        filled
+    }
+
+    pub fn charged(&self) -> bool {
+        self.boost > 0
+    }
+
+    pub fn multiplier(&self) -> u16 {
+        if self.charged() { MULTIPLIER } else { 1 }
+    }
+
+    // How full the gauge is, the boost counting down once it pays out
+    pub fn percent(&self) -> u16 {
+        if self.charged() {
+            (self.boost * 100 / BOOST_TICKS) as u16
+        } else {
+            (self.charge * 100.0) as u16
+        }
+    }
+}
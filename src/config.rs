@@ -0,0 +1,384 @@
+use std::path::PathBuf;
+
+use crate::{
+    app::{BALL_SIZES, PADDLE_HEIGHTS, PADDLE_WIDTHS, SPEEDS},
+    court::Court,
+    modifiers::Modifiers,
+    palette::PALETTES,
+    settings::{Acceleration, Difficulty, PADDLE_SPEEDS},
+    sound::BACKENDS,
+};
+
+pub const USAGE: &str = "\
+usage: pong_terminal [options]
+       pong_terminal simulate [--games <n>] [options]
+
+simulate plays games between two cpu paddles as fast as it can and prints how they
+went, the game options below set them up.
+
+options:
+    --profile <name>
+                    play as this profile, creating it if needed, instead of
+                    picking one at startup
+    --screensaver   watch the cpu play itself in changing colours until any
+                    key is pressed
+    --procedural    endless mode with generated brick layouts
+    --seed <n>      seed for the random number generator
+    --level <file>  play a brick layout saved by the editor
+    --edit <file>   open the level editor on a layout file
+    --games <n>     games for simulate to play (default 100)
+    --difficulty <name>
+                    how fast the cpu paddle moves: 'easy', 'normal' or 'hard',
+                    instead of the one in the settings
+    --win-score <n> points needed to win a game (default 10)
+    --best-of <n>   play a best-of-n series against the cpu, which then scores
+                    too whenever the ball gets past you
+    --time-attack   race to the winning score, with split times for every point
+                    against your fastest run
+    --rally         rally scoring, every return scores and long rallies
+                    multiply the points
+    --targets       target practice, send the ball up through the rings that
+                    come and go, the winning score has to be reached within a
+                    minute
+    --ball <WxH>    ball size in court units (default 5x5)
+    --paddle <WxH>  paddle size in court units (default 10x3)
+    --paddle-speed <n>
+                    court units your paddle moves a step of the arrows, from 1
+                    to 10 (default 5), instead of the one in the settings
+    --acceleration <name>
+                    how your paddle gets up to speed while an arrow is held
+                    and slows once it is let go: 'off', 'smooth' or 'heavy',
+                    instead of the one in the settings
+    --hardcore      one life, the first ball past you ends the run, which goes
+                    on a leaderboard of runs on the same --seed
+    --modifiers <list>
+                    match modifiers, any of 'mirror' for swapped left and
+                    right, 'blink' for a ball that vanishes now and then
+                    while away from the walls and paddles, 'walls' for a
+                    court that narrows every level until a stage is cleared
+                    and 'shadow' for a harmless decoy ball mirroring the
+                    real one, e.g. 'mirror,walls', instead of the ones in the
+                    settings
+    --practice      no game end, [ ] resize the paddle and - = the ball
+    --sideways      turn the court so the paddles sit on the left and right,
+                    which suits wide terminals
+    --vertical      up and down also move your paddle, within a band above
+                    its usual row
+    --max-speed <v> cap on how far the ball moves in one tick, in court units,
+                    however fast the game runs (default 4)
+    --slow-motion <f>
+                    how fast time runs for a moment after the ball just misses
+                    your paddle, from 0.1 to 1 where 1 switches it off
+                    (default 0.35)
+    --speed <f>     how fast the whole game runs, from 0.5 to 3, also changed
+                    with , and . while playing (default 1)
+    --shrink        your paddle gets a little narrower at every level up
+    --twitch <channel>
+                    let the channel's chat steer your paddle by typing left
+                    or right (needs the twitch feature)
+    --irc-server <host:port>
+                    chat server for --twitch (default irc.chat.twitch.tv:6667)
+    --export-telemetry <file>
+                    write the ball, paddles and events of every tick to a
+                    file when a game ends, as JSON for a .json file and CSV
+                    otherwise
+    --palette <name>
+                    colours for colour blind players: 'deuteranopia',
+                    'protanopia' or 'tritanopia', which also fill in the ball
+                    when it can be caught (default 'default')
+    --monochrome    no colours, only the terminal's own foreground and
+                    background with block graphics
+    --lang <code>   language for the interface, e.g. 'de', translations are
+                    read from locales/<code>.json (defaults to LANG)
+    --reduced-motion
+                    no flashing or moving decorations, colors stay steady
+    --idle-pause <s>
+                    pause and ask whether you are still there after this many
+                    seconds of play without a key press, 0 never does
+                    (default 60)
+    --no-confirm-quit
+                    quit a game in progress at the first q instead of asking,
+                    the game still counts as lost
+    --no-focus-pause
+                    keep playing, with sound, while the terminal window is not
+                    focused
+    --max-fps <n>   most frames drawn a second, whatever the game's tick rate,
+                    0 draws whenever something changes (default 60)
+    --low-bandwidth redraw less often with coarser graphics, for slow SSH links
+                    (switched on automatically when drawing is slow)
+    --log-level <filter>
+                    write logs to pong_terminal.log, e.g. 'info' or
+                    'warn,pong_terminal::level=debug' (defaults to RUST_LOG)
+    --record-cast <file>
+                    record the game as an asciinema cast, to watch again or
+                    share with 'asciinema play'
+    --quiet         print nothing about the last game after quitting
+    --json-summary  print the summary of the last game as one line of JSON,
+                    null if no game was played
+    --stats-file <file>
+                    write the score, level and ball speed once a second of
+                    play, one JSON object a line
+    --replay <dump> rerun the inputs recorded in a crash dump
+    --sound <name>  sound backend: 'rodio' (default), 'bell' to ring the
+                    terminal bell, e.g. over SSH, or 'silent' (alias --audio)
+    --audio-offset <ms>
+                    play the paddle click this much before the ball gets
+                    there, for speakers that lag behind, up to 200
+    -h, --help      print this message";
+
+#[derive(Clone, Default)]
+pub struct Config {
+    pub procedural: bool,
+    pub screensaver: bool,
+    pub simulate: bool,
+    pub games: Option<u16>,
+    pub difficulty: Option<Difficulty>,
+    pub profile: Option<String>,
+    pub seed: Option<u64>,
+    pub level: Option<PathBuf>,
+    pub edit: Option<PathBuf>,
+    pub win_score: Option<u16>,
+    pub best_of: Option<u16>,
+    pub rally: bool,
+    pub targets: bool,
+    pub time_attack: bool,
+    pub shrink: bool,
+    pub max_speed: Option<f64>,
+    pub slow_motion: Option<f64>,
+    pub speed: Option<f64>,
+    pub vertical: bool,
+    pub sideways: bool,
+    pub ball_size: Option<(f64, f64)>,
+    pub paddle_size: Option<(f64, f64)>,
+    pub paddle_speed: Option<u8>,
+    pub acceleration: Option<Acceleration>,
+    pub practice: bool,
+    pub hardcore: bool,
+    pub modifiers: Option<Modifiers>,
+    pub twitch: Option<String>,
+    pub irc_server: Option<String>,
+    pub export_telemetry: Option<PathBuf>,
+    pub low_bandwidth: bool,
+    pub max_fps: Option<u16>,
+    pub no_focus_pause: bool,
+    pub no_confirm_quit: bool,
+    pub idle_pause: Option<u16>,
+    pub reduced_motion: bool,
+    pub palette: Option<String>,
+    pub monochrome: bool,
+    pub lang: Option<String>,
+    pub log_level: Option<String>,
+    pub record_cast: Option<PathBuf>,
+    pub quiet: bool,
+    pub json_summary: bool,
+    pub stats_file: Option<PathBuf>,
+    pub replay: Option<PathBuf>,
+    pub audio: Option<String>,
+    pub audio_offset: Option<u16>,
+    pub help: bool,
+    pub args: Vec<String>, //the raw command line, kept for crash dumps
+}
+
+impl Config {
+    pub fn from_args<I: Iterator<Item = String>>(args: I) -> Result<Config, String> {
+        let mut config = Config {
+            args: args.collect(),
+            ..Config::default()
+        };
+        let mut args = config.args.clone().into_iter().peekable();
+        if args.next_if(|arg| arg == "simulate").is_some() {
+            config.simulate = true;
+        }
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--procedural" => config.procedural = true,
+                "--screensaver" => config.screensaver = true,
+                "--profile" => {
+                    let name = args.next().ok_or("--profile needs a name")?;
+                    if !crate::profile::valid_name(&name) {
+                        return Err(format!("invalid profile name '{}', use letters, digits, - and _", name));
+                    }
+                    config.profile = Some(name);
+                }
+                "--seed" => {
+                    let value = args.next().ok_or("--seed needs a value")?;
+                    let seed = value
+                        .parse()
+                        .map_err(|_| format!("invalid seed '{}'", value))?;
+                    config.seed = Some(seed);
+                }
+                "--level" => {
+                    config.level = Some(args.next().ok_or("--level needs a file")?.into());
+                }
+                "--edit" => {
+                    config.edit = Some(args.next().ok_or("--edit needs a file")?.into());
+                }
+                "--games" => config.games = Some(count(&arg, args.next())?),
+                "--difficulty" => {
+                    let name = args.next().ok_or("--difficulty needs a name")?;
+                    config.difficulty = Some(match name.as_str() {
+                        "easy" => Difficulty::Easy,
+                        "normal" => Difficulty::Normal,
+                        "hard" => Difficulty::Hard,
+                        _ => return Err(format!("unknown difficulty '{}'", name)),
+                    });
+                }
+                "--win-score" => config.win_score = Some(count(&arg, args.next())?),
+                "--best-of" => config.best_of = Some(count(&arg, args.next())?),
+                "--rally" => config.rally = true,
+                "--targets" => config.targets = true,
+                "--time-attack" => config.time_attack = true,
+                "--shrink" => config.shrink = true,
+                "--max-speed" => {
+                    let value = args.next().ok_or("--max-speed needs a value")?;
+                    match value.parse::<f64>() {
+                        Ok(speed) if speed >= 1.0 => config.max_speed = Some(speed),
+                        _ => return Err(format!("invalid max speed '{}', must be at least 1", value)),
+                    }
+                }
+                "--slow-motion" => {
+                    let value = args.next().ok_or("--slow-motion needs a value")?;
+                    match value.parse::<f64>() {
+                        Ok(factor) if (0.1..=1.0).contains(&factor) => config.slow_motion = Some(factor),
+                        _ => return Err(format!("invalid slow motion '{}', must be between 0.1 and 1", value)),
+                    }
+                }
+                "--speed" => {
+                    let value = args.next().ok_or("--speed needs a value")?;
+                    match value.parse::<f64>() {
+                        Ok(speed) if SPEEDS.contains(&speed) => config.speed = Some(speed),
+                        _ => return Err(format!("invalid speed '{}', must be between {} and {}", value, SPEEDS.start(), SPEEDS.end())),
+                    }
+                }
+                "--vertical" => config.vertical = true,
+                "--sideways" => config.sideways = true,
+                "--ball" => {
+                    let size = size(&arg, args.next())?;
+                    if !BALL_SIZES.contains(&size.0) || !BALL_SIZES.contains(&size.1) {
+                        return Err(format!("--ball must be between {0}x{0} and {1}x{1}", BALL_SIZES.start(), BALL_SIZES.end()));
+                    }
+                    config.ball_size = Some(size);
+                }
+                "--paddle" => {
+                    let size = size(&arg, args.next())?;
+                    if !PADDLE_WIDTHS.contains(&size.0) || !PADDLE_HEIGHTS.contains(&size.1) {
+                        return Err(format!(
+                            "--paddle must be between {}x{} and {}x{} on a {}x{} court",
+                            PADDLE_WIDTHS.start(),
+                            PADDLE_HEIGHTS.start(),
+                            PADDLE_WIDTHS.end(),
+                            PADDLE_HEIGHTS.end(),
+                            Court::FULL.width(),
+                            Court::FULL.height()
+                        ));
+                    }
+                    config.paddle_size = Some(size);
+                }
+                "--paddle-speed" => {
+                    let value = args.next().ok_or("--paddle-speed needs a value")?;
+                    match value.parse::<u8>() {
+                        Ok(speed) if PADDLE_SPEEDS.contains(&speed) => config.paddle_speed = Some(speed),
+                        _ => return Err(format!("invalid paddle speed '{}', must be between {} and {}", value, PADDLE_SPEEDS.start(), PADDLE_SPEEDS.end())),
+                    }
+                }
+                "--acceleration" => {
+                    let name = args.next().ok_or("--acceleration needs a name")?;
+                    config.acceleration = Some(match name.as_str() {
+                        "off" => Acceleration::Off,
+                        "smooth" => Acceleration::Smooth,
+                        "heavy" => Acceleration::Heavy,
+                        _ => return Err(format!("unknown acceleration '{}'", name)),
+                    });
+                }
+                "--practice" => config.practice = true,
+                "--hardcore" => config.hardcore = true,
+                "--modifiers" => {
+                    let list = args.next().ok_or("--modifiers needs a list")?;
+                    config.modifiers = Some(Modifiers::parse(&list)?);
+                }
+                "--twitch" if cfg!(feature = "twitch") => {
+                    config.twitch = Some(args.next().ok_or("--twitch needs a channel")?);
+                }
+                "--twitch" => return Err(String::from("built without the twitch feature")),
+                "--irc-server" => {
+                    config.irc_server = Some(args.next().ok_or("--irc-server needs a host:port")?);
+                }
+                "--export-telemetry" => {
+                    config.export_telemetry = Some(args.next().ok_or("--export-telemetry needs a file")?.into());
+                }
+                "--low-bandwidth" => config.low_bandwidth = true,
+                "--max-fps" => {
+                    let value = args.next().ok_or("--max-fps needs a number")?;
+                    config.max_fps = Some(value.parse().map_err(|_| format!("invalid max fps '{}'", value))?);
+                }
+                "--no-focus-pause" => config.no_focus_pause = true,
+                "--no-confirm-quit" => config.no_confirm_quit = true,
+                "--idle-pause" => {
+                    let value = args.next().ok_or("--idle-pause needs a number of seconds")?;
+                    config.idle_pause = Some(value.parse().map_err(|_| format!("invalid idle pause '{}'", value))?);
+                }
+                "--reduced-motion" => config.reduced_motion = true,
+                "--monochrome" => config.monochrome = true,
+                "--lang" => config.lang = Some(args.next().ok_or("--lang needs a language code")?),
+                "--palette" => {
+                    let name = args.next().ok_or("--palette needs a name")?;
+                    if !PALETTES.contains(&name.as_str()) {
+                        return Err(format!("unknown palette '{}'", name));
+                    }
+                    config.palette = Some(name);
+                }
+                "--log-level" => {
+                    config.log_level = Some(args.next().ok_or("--log-level needs a filter")?);
+                }
+                "--record-cast" => {
+                    config.record_cast = Some(args.next().ok_or("--record-cast needs a file")?.into());
+                }
+                "--quiet" => config.quiet = true,
+                "--json-summary" => config.json_summary = true,
+                "--stats-file" => {
+                    config.stats_file = Some(args.next().ok_or("--stats-file needs a file")?.into());
+                }
+                "--replay" => {
+                    config.replay = Some(args.next().ok_or("--replay needs a file")?.into());
+                }
+                "--sound" | "--audio" => {
+                    let name = args.next().ok_or("--sound needs a backend")?;
+                    if !BACKENDS.contains(&name.as_str()) {
+                        return Err(format!("unknown sound backend '{}'", name));
+                    }
+                    config.audio = Some(name);
+                }
+                "--audio-offset" => {
+                    let value = args.next().ok_or("--audio-offset needs a number of milliseconds")?;
+                    match value.parse::<u16>() {
+                        Ok(ms) if ms <= 200 => config.audio_offset = Some(ms),
+                        _ => return Err(format!("invalid audio offset '{}', must be 0 to 200", value)),
+                    }
+                }
+                "-h" | "--help" => config.help = true,
+                _ => return Err(format!("unknown option '{}'", arg)),
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+// A positive number following an option
+fn count(option: &str, value: Option<String>) -> Result<u16, String> {
+    let value = value.ok_or_else(|| format!("{} needs a number", option))?;
+    match value.parse() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!("invalid {} '{}'", option, value)),
+    }
+}
+
+// A size written as WIDTHxHEIGHT, e.g. 10x3
+fn size(option: &str, value: Option<String>) -> Result<(f64, f64), String> {
+    let value = value.ok_or_else(|| format!("{} needs a size like 10x3", option))?;
+    value
+        .split_once('x')
+        .and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?)))
+        .ok_or_else(|| format!("invalid {} size '{}'", option, value))
+}
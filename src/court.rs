@@ -0,0 +1,98 @@
+use ratatui::layout::Rect;
+
+// The court in world units, the ones the ball, paddles and everything else on it move in,
+// and how they map onto the canvas and the terminal's cells. y runs up from the player's
+// end, the other way to the terminal's rows, whichever way round the court is drawn
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Court {
+    x: u16,
+    y: u16,
+    width: u16,
+    height: u16,
+}
+
+impl Court {
+    // Every game starts on this court, before any walls close in
+    pub const FULL: Court = Court {
+        x: 10,
+        y: 10,
+        width: 150,
+        height: 100,
+    };
+
+    pub const fn left(self) -> f64 {
+        self.x as f64
+    }
+
+    pub const fn right(self) -> f64 {
+        (self.x + self.width) as f64
+    }
+
+    // The player's end
+    pub const fn bottom(self) -> f64 {
+        self.y as f64
+    }
+
+    // The cpu's end
+    pub const fn top(self) -> f64 {
+        (self.y + self.height) as f64
+    }
+
+    pub const fn width(self) -> f64 {
+        self.width as f64
+    }
+
+    pub const fn height(self) -> f64 {
+        self.height as f64
+    }
+
+    // The canvas bounds that show the court upright, left to right and bottom to top
+    pub const fn x_bounds(self) -> [f64; 2] {
+        [self.left(), self.right()]
+    }
+
+    pub const fn y_bounds(self) -> [f64; 2] {
+        [self.bottom(), self.top()]
+    }
+
+    // The point `fraction` of the way across from the left wall
+    pub fn across(self, fraction: f64) -> f64 {
+        self.left() + self.width() * fraction
+    }
+
+    // The height `fraction` of the way up from the player's end
+    pub fn along(self, fraction: f64) -> f64 {
+        self.bottom() + self.height() * fraction
+    }
+
+    // The same corner with another size
+    pub fn resized(self, width: u16, height: u16) -> Court {
+        Court { width, height, ..self }
+    }
+
+    // The court with both side walls moved in by `by`
+    pub fn inset(self, by: u16) -> Court {
+        let by = by.min(self.width / 2);
+        Court {
+            x: self.x + by,
+            width: self.width - 2 * by,
+            ..self
+        }
+    }
+
+    // How far the side walls have moved in from the full court
+    pub fn walls(self) -> u16 {
+        self.x.saturating_sub(Court::FULL.x)
+    }
+
+    // The point of the court under a terminal cell, with the court drawn upright across
+    // all of `cells`, if the cell is one of them
+    pub fn point_at(self, cells: Rect, column: u16, row: u16) -> Option<(f64, f64)> {
+        if column < cells.left() || column >= cells.right() || row < cells.top() || row >= cells.bottom() {
+            return None;
+        }
+        let x = self.left() + (column - cells.x) as f64 / cells.width as f64 * self.width();
+        let y = self.top() - (row - cells.y) as f64 / cells.height as f64 * self.height();
+        Some((x, y))
+    }
+}
@@ -0,0 +1,71 @@
+use std::time::{Duration, Instant};
+
+use crate::render::FrameStats;
+
+// A draw this slow means the terminal is not keeping up, usually a slow SSH link
+const SLOW_DRAW: Duration = Duration::from_millis(20);
+const SLOW_DRAWS: u32 = 20;
+
+pub struct Diagnostics {
+    pub visible: bool,
+    pub fps: f64,
+    pub tick_time: Duration,
+    pub steps: u32, //ticks run to catch up before the last frame
+    pub poll_latency: Duration,
+    pub draw_time: Duration,
+    pub output: FrameStats, //of the last frame
+    pub bytes_per_second: f64,
+    bytes: usize,
+    slow_draws: u32,
+    frames: u32,
+    window_start: Instant,
+}
+
+impl Diagnostics {
+    pub fn new() -> Diagnostics {
+        Diagnostics {
+            visible: false,
+            fps: 0.0,
+            tick_time: Duration::ZERO,
+            steps: 0,
+            poll_latency: Duration::ZERO,
+            draw_time: Duration::ZERO,
+            output: FrameStats::default(),
+            bytes_per_second: 0.0,
+            bytes: 0,
+            slow_draws: 0,
+            frames: 0,
+            window_start: Instant::now(),
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    // Averages the frame count and output over roughly half a second so the numbers stay
+    // readable
+    pub fn frame(&mut self, output: FrameStats) {
+        self.frames += 1;
+        self.output = output;
+        self.bytes += output.bytes;
+        let elapsed = self.window_start.elapsed();
+        if elapsed >= Duration::from_millis(500) {
+            self.fps = self.frames as f64 / elapsed.as_secs_f64();
+            self.bytes_per_second = self.bytes as f64 / elapsed.as_secs_f64();
+            self.frames = 0;
+            self.bytes = 0;
+            self.window_start = Instant::now();
+        }
+    }
+
+    pub fn draw(&mut self, elapsed: Duration) {
+        self.draw_time = elapsed;
+        self.slow_draws = if elapsed >= SLOW_DRAW { self.slow_draws + 1 } else { 0 };
+    }
+
+    // True once enough draws in a row have been slow
+    pub fn slow_link(&self) -> bool {
+        self.slow_draws >= SLOW_DRAWS
+    }
+}
@@ -0,0 +1,80 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use ratatui::{style::Color, widgets::canvas::Rectangle};
+
+use crate::{
+    level::{self, BrickKind, Level, COLUMNS, MAX_ROWS},
+    locale::Locale,
+};
+
+pub struct Editor {
+    pub path: PathBuf,
+    pub level: Level,
+    pub cursor: (usize, usize),
+    pub status: String,
+}
+
+impl Editor {
+    pub fn open(path: &Path, locale: &Locale) -> io::Result<Editor> {
+        let (level, status) = if path.exists() {
+            (Level::load(path)?, locale.format("editor.editing", &[&path.display()]))
+        } else {
+            (Level::empty(), locale.format("editor.new", &[&path.display()]))
+        };
+
+        Ok(Editor {
+            path: path.to_path_buf(),
+            level,
+            cursor: (0, 0),
+            status,
+        })
+    }
+
+    pub fn move_cursor(&mut self, rows: isize, cols: isize) {
+        let (row, col) = self.cursor;
+        self.cursor = (
+            (row as isize + rows).clamp(0, MAX_ROWS as isize - 1) as usize,
+            (col as isize + cols).clamp(0, COLUMNS as isize - 1) as usize,
+        );
+    }
+
+    // empty -> brick -> obstacle -> empty
+    pub fn cycle(&mut self, row: usize, col: usize) {
+        let next = match self.level.get(row, col) {
+            None => Some(BrickKind::Breakable),
+            Some(BrickKind::Breakable) => Some(BrickKind::Obstacle),
+            Some(BrickKind::Obstacle) => None,
+        };
+        self.level.set(row, col, next);
+        self.cursor = (row, col);
+    }
+
+    pub fn clear(&mut self) {
+        let (row, col) = self.cursor;
+        self.level.set(row, col, None);
+    }
+
+    pub fn click(&mut self, x: f64, y: f64) {
+        if let Some((row, col)) = level::cell_at(x, y) {
+            self.cycle(row, col);
+        }
+    }
+
+    pub fn save(&mut self, locale: &Locale) {
+        self.status = match self.level.save(&self.path) {
+            Ok(()) => locale.format("editor.saved", &[&self.path.display()]),
+            Err(err) => locale.format("editor.save_failed", &[&err]),
+        };
+    }
+
+    pub fn cursor_rect(&self) -> Rectangle {
+        let (row, col) = self.cursor;
+        Rectangle {
+            color: Color::Yellow,
+            ..level::cell_rect(row, col)
+        }
+    }
+}
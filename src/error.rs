@@ -0,0 +1,31 @@
+use std::{io, path::PathBuf};
+
+use thiserror::Error;
+
+// Why the game couldn't start or had to stop, each saying what it was doing at the time.
+// These are printed once the terminal is back to normal
+#[derive(Debug, Error)]
+pub enum PongError {
+    #[error("can't open the log file {}: {source}", crate::logging::LOG_FILE)]
+    Logging { source: io::Error },
+    #[error("can't read the crash dump {}: {source}", path.display())]
+    Replay { path: PathBuf, source: io::Error },
+    #[error("the crash dump has a bad command line: {0}")]
+    ReplayArgs(String),
+    #[error("can't load the level {}: {source}", path.display())]
+    Level { path: PathBuf, source: io::Error },
+    #[error("can't open {} in the editor: {source}", path.display())]
+    Editor { path: PathBuf, source: io::Error },
+    #[error("can't record to {}: {source}", path.display())]
+    Cast { path: PathBuf, source: io::Error },
+    #[error("can't write stats to {}: {source}", path.display())]
+    Stats { path: PathBuf, source: io::Error },
+    #[error("can't join the chat on {server}: {source}")]
+    Chat { server: String, source: io::Error },
+    #[error("can't start the game loop: {0}")]
+    Runtime(io::Error),
+    #[error("can't set up the terminal: {0}")]
+    Terminal(io::Error),
+    #[error("the game stopped: {0}")]
+    Game(io::Error),
+}
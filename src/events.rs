@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+use crate::series::Winner;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Paddle {
+    Player,
+    Cpu,
+}
+
+// Things that happened during a tick, for audio and anything else that wants to react
+// without reaching into the physics
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameEvent {
+    BallHitPaddle { paddle: Paddle, multiplier: u16 },
+    BallHitWall,
+    BallHitBrick { broken: bool },
+    PointScored,
+    LifeLost,
+    LevelUp(u64),
+    StageCleared(u32),
+    TargetHit,
+    BossHit(u16), //health left
+    ComboFull,
+    Win(Winner),
+}
+
+impl GameEvent {
+    // What scripts see, just the kind of event
+    pub fn name(&self) -> &'static str {
+        match self {
+            GameEvent::BallHitPaddle { .. } => "BallHitPaddle",
+            GameEvent::BallHitWall => "BallHitWall",
+            GameEvent::BallHitBrick { .. } => "BallHitBrick",
+            GameEvent::PointScored => "PointScored",
+            GameEvent::LifeLost => "LifeLost",
+            GameEvent::LevelUp(_) => "LevelUp",
+            GameEvent::StageCleared(_) => "StageCleared",
+            GameEvent::TargetHit => "TargetHit",
+            GameEvent::BossHit(_) => "BossHit",
+            GameEvent::ComboFull => "ComboFull",
+            GameEvent::Win(_) => "Win",
+        }
+    }
+}
@@ -0,0 +1,230 @@
+use std::{
+    collections::VecDeque,
+    fmt, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crossterm::event::KeyCode;
+
+pub const STATE_TICKS: usize = 250; //about six seconds of play
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Input {
+    Key(KeyCode),
+    Click(f64, f64),
+    Demo,
+    Focus(bool), //the terminal window gained or lost focus
+    Release(KeyCode), //from terminals that report key releases
+}
+
+// Inputs that come in on their own instead of from the terminal, like a replay or a
+// recording played into a test. Each is due at a simulation step, the number of ticks the
+// game has run when it arrived
+pub trait InputSource {
+    // The next input due by `step`, None while the rest are still to come
+    fn next_input(&mut self, step: u64) -> Option<Input>;
+    fn finished(&self) -> bool;
+}
+
+// The inputs in the order they came, as a replay file lists them
+impl InputSource for VecDeque<(u64, Input)> {
+    fn next_input(&mut self, step: u64) -> Option<Input> {
+        match self.front() {
+            Some(&(due, _)) if due <= step => self.pop_front().map(|(_, input)| input),
+            _ => None,
+        }
+    }
+
+    fn finished(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+#[derive(Clone)]
+pub struct Snapshot {
+    pub step: u64,
+    pub tick: u64,
+    pub ball: (f64, f64),
+    pub dir: (bool, bool),
+    pub velocity: (f64, f64),
+    pub rx: f64,
+    pub board_x: f64,
+    pub cpu_x: f64,
+    pub score: u16,
+}
+
+impl fmt::Display for Snapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "state {} tick={} ball=({:.3},{:.3}) dir=({},{}) v=({:.2},{:.2}) rx={:.1} board={:.1} cpu={:.1} score={}",
+            self.step,
+            self.tick,
+            self.ball.0,
+            self.ball.1,
+            self.dir.0,
+            self.dir.1,
+            self.velocity.0,
+            self.velocity.1,
+            self.rx,
+            self.board_x,
+            self.cpu_x,
+            self.score
+        )
+    }
+}
+
+// Everything needed to rerun a session: the seed, the command line and every input
+// stamped with the number of simulation steps that had run when it arrived
+pub struct History {
+    pub seed: u64,
+    pub args: Vec<String>,
+    pub releases: bool, //whether the terminal reported key releases, held keys depend on it
+    pub step: u64,
+    inputs: Vec<(u64, Input)>,
+    states: VecDeque<Snapshot>,
+}
+
+impl History {
+    pub fn new(seed: u64, args: Vec<String>) -> History {
+        History {
+            seed,
+            args,
+            releases: false,
+            step: 0,
+            inputs: Vec::new(),
+            states: VecDeque::with_capacity(STATE_TICKS),
+        }
+    }
+
+    pub fn record_input(&mut self, input: Input) {
+        self.inputs.push((self.step, input));
+    }
+
+    pub fn record_state(&mut self, snapshot: Snapshot) {
+        if self.states.len() == STATE_TICKS {
+            self.states.pop_front();
+        }
+        self.states.push_back(snapshot);
+        self.step += 1;
+    }
+
+    pub fn write_dump(&self, reason: &str) -> io::Result<PathBuf> {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let path = PathBuf::from(format!("pong_terminal-crash-{}.txt", secs));
+
+        let mut file = io::BufWriter::new(fs::File::create(&path)?);
+        writeln!(file, "# pong_terminal crash dump, replay with --replay {}", path.display())?;
+        writeln!(file, "reason {}", reason.replace('\n', " "))?;
+        writeln!(file, "seed {}", self.seed)?;
+        writeln!(file, "args {}", self.args.join("\t"))?;
+        writeln!(file, "releases {}", self.releases as u8)?;
+        for (step, input) in &self.inputs {
+            writeln!(file, "input {} {}", step, encode(input))?;
+        }
+        for state in &self.states {
+            writeln!(file, "{}", state)?;
+        }
+        file.flush()?;
+        Ok(path)
+    }
+}
+
+pub struct Replay {
+    pub seed: u64,
+    pub args: Vec<String>,
+    pub releases: bool,
+    pub inputs: VecDeque<(u64, Input)>,
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> io::Result<Replay> {
+        let invalid = |line: usize| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{}: bad line {}", path.display(), line + 1),
+            )
+        };
+
+        let mut replay = Replay {
+            seed: 0,
+            args: Vec::new(),
+            releases: false,
+            inputs: VecDeque::new(),
+        };
+        for (n, line) in fs::read_to_string(path)?.lines().enumerate() {
+            let (kind, rest) = line.split_once(' ').unwrap_or((line, ""));
+            match kind {
+                "seed" => replay.seed = rest.parse().map_err(|_| invalid(n))?,
+                "args" => replay.args = rest.split('\t').filter(|a| !a.is_empty()).map(String::from).collect(),
+                "releases" => replay.releases = rest == "1",
+                "input" => {
+                    let (step, input) = rest.split_once(' ').ok_or_else(|| invalid(n))?;
+                    let step = step.parse().map_err(|_| invalid(n))?;
+                    replay.inputs.push_back((step, decode(input).ok_or_else(|| invalid(n))?));
+                }
+                _ => {}
+            }
+        }
+        Ok(replay)
+    }
+}
+
+fn encode(input: &Input) -> String {
+    match input {
+        Input::Key(code) => format!("key {}", key_name(*code)),
+        Input::Click(x, y) => format!("click {} {}", x, y),
+        Input::Demo => String::from("demo"),
+        Input::Focus(focused) => format!("focus {}", *focused as u8),
+        Input::Release(code) => format!("release {}", key_name(*code)),
+    }
+}
+
+fn decode(text: &str) -> Option<Input> {
+    let mut words = text.split(' ');
+    match words.next()? {
+        "key" => Some(Input::Key(key_code(words.next()?, words.next())?)),
+        "click" => Some(Input::Click(words.next()?.parse().ok()?, words.next()?.parse().ok()?)),
+        "demo" => Some(Input::Demo),
+        "focus" => Some(Input::Focus(words.next()? == "1")),
+        "release" => Some(Input::Release(key_code(words.next()?, words.next())?)),
+        _ => None,
+    }
+}
+
+fn key_name(code: KeyCode) -> String {
+    match code {
+        KeyCode::Char(c) => format!("char {}", c as u32),
+        KeyCode::F(n) => format!("f {}", n),
+        KeyCode::Left => String::from("left"),
+        KeyCode::Right => String::from("right"),
+        KeyCode::Up => String::from("up"),
+        KeyCode::Down => String::from("down"),
+        KeyCode::Enter => String::from("enter"),
+        KeyCode::Esc => String::from("esc"),
+        KeyCode::Backspace => String::from("backspace"),
+        KeyCode::Delete => String::from("delete"),
+        _ => String::from("other"),
+    }
+}
+
+fn key_code(name: &str, arg: Option<&str>) -> Option<KeyCode> {
+    Some(match name {
+        "char" => KeyCode::Char(char::from_u32(arg?.parse().ok()?)?),
+        "f" => KeyCode::F(arg?.parse().ok()?),
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "enter" => KeyCode::Enter,
+        "esc" => KeyCode::Esc,
+        "backspace" => KeyCode::Backspace,
+        "delete" => KeyCode::Delete,
+        "other" => KeyCode::Null,
+        _ => return None,
+    })
+}
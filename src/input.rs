@@ -0,0 +1,457 @@
+use crossterm::event::KeyCode;
+use log::{debug, info, warn};
+
+use crate::{
+    app::{next_game, reset, serve, App, Mode, Orientation},
+    history::{Input, InputSource},
+    menu::Item,
+    results::Choice,
+    save,
+    settings::{self, Row},
+};
+
+// Records the input so a crash dump can reproduce the session, then acts on it.
+// Returns true when the app should quit
+pub fn apply_input(app: &mut App, input: Input) -> bool {
+    app.history.record_input(input);
+    match input {
+        Input::Key(code) => {
+            app.idle_ticks = 0;
+            handle_key(app, code)
+        }
+        Input::Click(x, y) => {
+            if let Some(editor) = app.editor.as_mut() {
+                editor.click(x, y);
+            }
+            false
+        }
+        Input::Demo => {
+            reset(app);
+            serve(app);
+            app.set_mode(Mode::Demo);
+            false
+        }
+        Input::Focus(focused) => {
+            focus(app, focused);
+            false
+        }
+        Input::Release(code) => {
+            if app.held == Some(court_key(app, bound_key(app, code))) {
+                app.held = None;
+            }
+            false
+        }
+    }
+}
+
+// Switching to another window mutes the game and pauses a game in progress, coming back
+// only resumes a game that was paused this way
+fn focus(app: &mut App, focused: bool) {
+    if !focused {
+        app.held = None; //the release goes to another window
+    }
+    if !app.focus_pause {
+        return;
+    }
+    if focused {
+        app.apply_settings();
+        if app.paused_by_focus && app.mode() == Mode::Paused {
+            app.set_mode(Mode::Play);
+        }
+        app.paused_by_focus = false;
+    } else {
+        app.audio.set_volume(0.0);
+        if app.mode() == Mode::Play && !app.game_over() {
+            info!("focus lost, pausing");
+            app.set_mode(Mode::Paused);
+            app.paused_by_focus = true;
+        }
+    }
+}
+
+// Feeds recorded inputs due at the current step, handing control back once they run out
+pub fn replay_inputs(app: &mut App) -> bool {
+    while let Some(queue) = app.replay.as_mut() {
+        if queue.finished() {
+            info!("replay finished at step {}", app.history.step);
+            app.replay = None;
+            break;
+        }
+        match queue.next_input(app.history.step) {
+            Some(input) if apply_input(app, input) => return true,
+            Some(_) => {}
+            None => break,
+        }
+    }
+    false
+}
+
+// Chat votes go through the same path as keys, so they end up in crash dumps too
+#[cfg(feature = "twitch")]
+pub fn chat_inputs(app: &mut App) {
+    if app.mode() != Mode::Play || app.replay.is_some() {
+        return;
+    }
+    if let Some(code) = app.chat.as_mut().and_then(|chat| chat.poll()) {
+        apply_input(app, Input::Key(code));
+        apply_input(app, Input::Release(code)); //a vote is one step, never a held key
+    }
+}
+
+// Returns true when the app should quit
+fn handle_key(app: &mut App, code: KeyCode) -> bool {
+    debug!("key {:?} in {:?}", code, app.mode());
+    if app.screensaver {
+        return true;
+    }
+    if code == KeyCode::F(3) {
+        app.debug.toggle();
+        return false;
+    }
+    if code == KeyCode::F(12) {
+        app.screenshot = true;
+        return false;
+    }
+    let help_key = matches!(code, KeyCode::Char('?') | KeyCode::F(1));
+    if app.help {
+        app.help = !(help_key || code == KeyCode::Esc);
+        app.menu.touch();
+        return false;
+    }
+    if help_key && app.mode() != Mode::Demo {
+        app.help = true;
+        return false;
+    }
+
+    if app.idle {
+        app.idle = false;
+        if app.mode() == Mode::Paused && code != KeyCode::Char('q') {
+            app.set_mode(Mode::Play); //any key answers, it isn't taken as a move
+            return false;
+        }
+    }
+
+    if std::mem::take(&mut app.quitting) {
+        if code == KeyCode::Char('y') {
+            app.abandon();
+            return true;
+        }
+        app.back();
+        return false;
+    }
+
+    app.screen().handle_input(app, code)
+}
+
+pub fn menu_key(app: &mut App, code: KeyCode) -> bool {
+    app.menu.touch();
+    match code {
+        KeyCode::Char('q') => return true,
+        KeyCode::Up => app.menu.up(),
+        KeyCode::Down => app.menu.down(),
+        KeyCode::Enter => match app.menu.item() {
+            Item::Play | Item::Procedural => {
+                app.procedural = app.menu.item() == Item::Procedural;
+                reset(app);
+                serve(app);
+                app.set_mode(Mode::Play);
+            }
+            Item::Continue => match save::load(app) {
+                Ok(()) => {
+                    info!("resumed saved game at tick {}", app.tick_count);
+                    app.set_mode(Mode::Paused);
+                }
+                Err(err) => warn!("can't load {}: {}", app.file(save::SAVE_FILE).display(), err),
+            },
+            Item::Settings => open_settings(app),
+            Item::Profile => {
+                app.profiles.refresh();
+                app.set_mode(Mode::Profiles);
+            }
+            Item::Quit => return true,
+        },
+        _ => {}
+    }
+    false
+}
+
+pub fn end_demo(app: &mut App) {
+    reset(app);
+    app.menu.touch();
+    app.set_mode(Mode::Menu);
+}
+
+pub fn editor_key(app: &mut App, code: KeyCode) -> bool {
+    let editor = app.editor.as_mut().unwrap();
+    match code {
+        KeyCode::Char('q') => return true,
+        KeyCode::Up => editor.move_cursor(-1, 0),
+        KeyCode::Down => editor.move_cursor(1, 0),
+        KeyCode::Left => editor.move_cursor(0, -1),
+        KeyCode::Right => editor.move_cursor(0, 1),
+        KeyCode::Char(' ') => {
+            let (row, col) = editor.cursor;
+            editor.cycle(row, col);
+        }
+        KeyCode::Delete | KeyCode::Backspace => editor.clear(),
+        KeyCode::Char('s') => editor.save(&app.locale),
+        KeyCode::Char('p') => {
+            app.custom_level = Some(editor.level.clone());
+            reset(app);
+            serve(app);
+            app.set_mode(Mode::Play);
+        }
+        _ => {}
+    }
+    false
+}
+
+pub fn paused_key(app: &mut App, code: KeyCode) -> bool {
+    match code {
+        KeyCode::Char('q') => return quit(app),
+        KeyCode::Char('p') => app.set_mode(Mode::Play),
+        KeyCode::Char('o') => open_settings(app),
+        KeyCode::Char('d') => app.debug.toggle(),
+        KeyCode::Char('h') => app.zen = !app.zen,
+        KeyCode::Char('s') => save_game(app),
+        KeyCode::Esc => to_menu(app),
+        _ => {}
+    }
+    false
+}
+
+// The court's keys, arrows as the paddles lie on screen
+pub fn play_key(app: &mut App, code: KeyCode) -> bool {
+    match court_key(app, bound_key(app, code)) {
+        code if app.press_arrow(code) => {}
+        KeyCode::Char('q') => return quit(app),
+        KeyCode::Char('r') => restart(app),
+        KeyCode::Char('p') => app.set_mode(Mode::Paused),
+        KeyCode::Char('d') => app.debug.toggle(),
+        KeyCode::Char('h') => app.zen = !app.zen,
+        KeyCode::Char('s') => save_game(app),
+        KeyCode::Char('i') if app.instant.offered(app.tick_count) => app.start_instant_replay(),
+        KeyCode::Char(',') => app.change_speed(false),
+        KeyCode::Char('.') => app.change_speed(true),
+        KeyCode::Char('[') if app.practice => app.resize_paddle(-1.0),
+        KeyCode::Char(']') if app.practice => app.resize_paddle(1.0),
+        KeyCode::Char('-') if app.practice => app.resize_ball(-1.0),
+        KeyCode::Char('=') if app.practice => app.resize_ball(1.0),
+        KeyCode::Enter if app.next_game_ready() => {
+            next_game(app);
+            serve(app);
+        }
+        KeyCode::Enter if app.can_keep_playing() => app.keep_playing(),
+        KeyCode::Char('e') if app.editor.is_some() => app.set_mode(Mode::Edit),
+        KeyCode::Esc => to_menu(app),
+        _ => {}
+    }
+    false
+}
+
+// The choices after a win, q, r and esc still do what they do on the court
+pub fn results_key(app: &mut App, code: KeyCode) -> bool {
+    let Some(results) = &mut app.results else {
+        return false;
+    };
+    match code {
+        KeyCode::Up => results.up(),
+        KeyCode::Down => results.down(),
+        KeyCode::Enter => match results.choice() {
+            Choice::NextGame => {
+                next_game(app);
+                serve(app);
+            }
+            Choice::KeepPlaying => app.keep_playing(),
+            Choice::Rematch => app.rematch(),
+            Choice::Restart => restart(app),
+            Choice::Menu => to_menu(app),
+            Choice::Quit => return quit(app),
+        },
+        KeyCode::Char('q') => return quit(app),
+        KeyCode::Char('r') => restart(app),
+        KeyCode::Esc => to_menu(app),
+        _ => {}
+    }
+    false
+}
+
+// Asks before leaving a game in progress, unless switched off, returns true to quit now
+pub fn quit(app: &mut App) -> bool {
+    if app.confirm_quit && app.in_progress() && !app.practice {
+        app.quitting = true;
+        app.open(Mode::Paused);
+        return false;
+    }
+    app.abandon();
+    true
+}
+
+// The keys that move the paddle `way` on the court however they are mapped, the arrows as
+// they lie on screen and then the keys bound to them, e.g. "←,a"
+pub fn keys_for(app: &App, way: KeyCode) -> String {
+    let arrows = [(KeyCode::Left, '←'), (KeyCode::Right, '→'), (KeyCode::Up, '↑'), (KeyCode::Down, '↓')];
+    let bound = [app.settings.left, app.settings.right].into_iter().flatten().map(|key| (KeyCode::Char(key), key));
+    let keys: Vec<String> = arrows
+        .into_iter()
+        .chain(bound)
+        .filter(|&(code, _)| court_key(app, bound_key(app, code)) == way)
+        .map(|(_, name)| name.to_string())
+        .collect();
+    keys.join(",")
+}
+
+// Keys bound on the settings screen stand in for the arrows
+fn bound_key(app: &App, code: KeyCode) -> KeyCode {
+    match code {
+        KeyCode::Char(c) if app.settings.left == Some(c) => KeyCode::Left,
+        KeyCode::Char(c) if app.settings.right == Some(c) => KeyCode::Right,
+        _ => code,
+    }
+}
+
+fn open_settings(app: &mut App) {
+    app.settings_menu.binding = false;
+    app.open(Mode::Settings);
+}
+
+// Every change shows at once, the file is written when the screen is left
+pub fn settings_key(app: &mut App, code: KeyCode) {
+    let row = app.settings_menu.row();
+    if app.settings_menu.binding {
+        app.settings_menu.binding = false;
+        if let KeyCode::Char(c) = code {
+            if !settings::bind(&mut app.settings, row, c) {
+                app.post_notice(app.locale.format("settings.taken", &[&c]));
+            }
+        }
+        return;
+    }
+
+    match code {
+        KeyCode::Up => app.settings_menu.up(),
+        KeyCode::Down => app.settings_menu.down(),
+        KeyCode::Left | KeyCode::Right => {
+            settings::step(&mut app.settings, row, code == KeyCode::Right);
+            app.apply_settings();
+        }
+        KeyCode::Enter => {
+            if !settings::step(&mut app.settings, row, true) {
+                app.settings_menu.binding = matches!(row, Row::LeftKey | Row::RightKey);
+            }
+            app.apply_settings();
+        }
+        KeyCode::Esc | KeyCode::Char('q') => {
+            let path = app.file(settings::SETTINGS_FILE);
+            let notice = match app.settings.save(&path) {
+                Ok(()) => app.locale.text("settings.saved"),
+                Err(err) => {
+                    warn!("can't write {}: {}", path.display(), err);
+                    app.locale.text("settings.save_failed")
+                }
+            };
+            app.post_notice(notice);
+            app.back();
+        }
+        _ => {}
+    }
+}
+
+// Picking a profile or typing the name of a new one, returns true to quit
+pub fn profiles_key(app: &mut App, code: KeyCode) -> bool {
+    let profiles = &mut app.profiles;
+    if let Some(name) = &mut profiles.name {
+        match code {
+            KeyCode::Char(c) => profiles.type_char(c),
+            KeyCode::Backspace => {
+                name.pop();
+            }
+            KeyCode::Enter if !name.is_empty() => {
+                let name = name.clone();
+                choose_profile(app, &name);
+            }
+            KeyCode::Esc if profiles.names.is_empty() => app.set_mode(Mode::Menu),
+            KeyCode::Esc => profiles.name = None,
+            _ => {}
+        }
+        return false;
+    }
+
+    match code {
+        KeyCode::Char('q') => return true,
+        KeyCode::Up => profiles.up(),
+        KeyCode::Down => profiles.down(),
+        KeyCode::Enter => match profiles.names.get(profiles.selected) {
+            Some(name) => {
+                let name = name.clone();
+                choose_profile(app, &name);
+            }
+            None => profiles.name = Some(String::new()),
+        },
+        KeyCode::Esc => app.set_mode(Mode::Menu),
+        _ => {}
+    }
+    false
+}
+
+fn choose_profile(app: &mut App, name: &str) {
+    match app.select_profile(name) {
+        Ok(()) => {
+            app.menu.touch();
+            app.set_mode(Mode::Menu);
+        }
+        Err(err) => warn!("can't open profile {}: {}", name, err),
+    }
+}
+
+// Arrow keys follow the paddles on screen, so a sideways court turns them back a quarter.
+// The mirror modifier then swaps them around
+fn court_key(app: &App, code: KeyCode) -> KeyCode {
+    let code = match (app.orientation, code) {
+        (Orientation::Sideways, KeyCode::Up) => KeyCode::Right,
+        (Orientation::Sideways, KeyCode::Down) => KeyCode::Left,
+        (Orientation::Sideways, KeyCode::Right) => KeyCode::Up,
+        (Orientation::Sideways, KeyCode::Left) => KeyCode::Down,
+        _ => code,
+    };
+    // with the ends swapped the court is upside down on screen, and so are up and down
+    let code = match code {
+        KeyCode::Up if app.flipped => KeyCode::Down,
+        KeyCode::Down if app.flipped => KeyCode::Up,
+        _ => code,
+    };
+    match code {
+        KeyCode::Left if app.modifiers.mirror => KeyCode::Right,
+        KeyCode::Right if app.modifiers.mirror => KeyCode::Left,
+        KeyCode::Up if app.modifiers.mirror => KeyCode::Down,
+        KeyCode::Down if app.modifiers.mirror => KeyCode::Up,
+        _ => code,
+    }
+}
+
+fn save_game(app: &mut App) {
+    match save::save(app) {
+        Ok(()) => {
+            info!("saved game at tick {}", app.tick_count);
+            app.menu.offer_continue();
+            app.post_notice(app.locale.text("notice.saved"));
+        }
+        Err(err) => {
+            warn!("can't write {}: {}", app.file(save::SAVE_FILE).display(), err);
+            app.post_notice(app.locale.text("notice.save_failed"));
+        }
+    }
+}
+
+// A game left for another counts as lost, the same as one quit
+fn restart(app: &mut App) {
+    app.abandon();
+    reset(app);
+}
+
+fn to_menu(app: &mut App) {
+    restart(app);
+    app.flipped = false; //a rematch's swapped ends last until the player leaves the match
+    app.menu.touch();
+    app.set_mode(if app.editor.is_some() { Mode::Edit } else { Mode::Menu });
+}
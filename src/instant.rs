@@ -0,0 +1,72 @@
+use std::collections::VecDeque;
+
+use crate::world::World;
+
+pub const REPLAY_TICKS: usize = 100; //the last four seconds of play
+const SLOWDOWN: usize = 3; //replay ticks spent on every recorded one
+const OFFER_TICKS: u64 = 80; //how long after a point `i` still replays it
+
+// What the court shows on one tick, everything in the world as it was then, bricks that
+// have since broken included
+#[derive(Clone)]
+pub struct Frame {
+    pub world: World,
+    pub catchable: bool,
+}
+
+// Rolling buffer of the last few seconds, played back in slow motion after a point or
+// a miss. The live frame is put aside while watching and put back at the end
+pub struct InstantReplay {
+    frames: VecDeque<Frame>,
+    offered_at: Option<u64>,
+    position: usize,
+    live: Option<Frame>,
+}
+
+impl InstantReplay {
+    pub fn new() -> InstantReplay {
+        InstantReplay {
+            frames: VecDeque::with_capacity(REPLAY_TICKS),
+            offered_at: None,
+            position: 0,
+            live: None,
+        }
+    }
+
+    pub fn record(&mut self, frame: Frame) {
+        if self.frames.len() == REPLAY_TICKS {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(frame);
+    }
+
+    pub fn offer(&mut self, tick: u64) {
+        self.offered_at = Some(tick);
+    }
+
+    pub fn offered(&self, tick: u64) -> bool {
+        self.offered_at.is_some_and(|at| tick < at + OFFER_TICKS)
+    }
+
+    pub fn start(&mut self, live: Frame) {
+        self.offered_at = None;
+        self.position = 0;
+        self.live = Some(live);
+    }
+
+    // The frame to show next, None once the replay has run out
+    pub fn step(&mut self) -> Option<Frame> {
+        let frame = self.frames.get(self.position / SLOWDOWN)?.clone();
+        self.position += 1;
+        Some(frame)
+    }
+
+    pub fn stop(&mut self) -> Option<Frame> {
+        self.live.take()
+    }
+
+    pub fn clear(&mut self) {
+        self.frames.clear();
+        self.offered_at = None;
+    }
+}
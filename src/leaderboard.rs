@@ -0,0 +1,54 @@
+use std::{fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+// Hardcore runs of every profile go on one board, next to the profiles directory
+pub const LEADERBOARD_FILE: &str = "pong_terminal-hardcore.json";
+const MAX_ENTRIES: usize = 100;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Entry {
+    pub name: Option<String>, //the profile, if one was picked
+    pub score: u16,
+    pub time: f64,
+    pub seed: u64,
+    #[serde(default)]
+    pub modifiers: Vec<String>, //the match modifiers that were on
+}
+
+// The best hardcore runs, most points first and the quicker run first on a tie. Runs on
+// the same seed with the same modifiers face the same game, so they are ranked against
+// each other
+#[derive(Default, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub entries: Vec<Entry>,
+}
+
+impl Leaderboard {
+    pub fn load(path: &Path) -> Leaderboard {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    // Adds the run and returns its place among the runs like it, counting from 1, and how
+    // many of those there are
+    pub fn add(&mut self, entry: Entry) -> (usize, usize) {
+        let alike = |other: &&Entry| other.seed == entry.seed && other.modifiers == entry.modifiers;
+        let at = self
+            .entries
+            .iter()
+            .position(|other| (other.score, -other.time) < (entry.score, -entry.time))
+            .unwrap_or(self.entries.len());
+        let rank = self.entries[..at].iter().filter(alike).count() + 1;
+        let runs = self.entries.iter().filter(alike).count() + 1;
+        self.entries.insert(at, entry);
+        self.entries.truncate(MAX_ENTRIES);
+        (rank, runs)
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+}
@@ -0,0 +1,405 @@
+use std::{collections::VecDeque, fs, io, ops::Range, path::Path};
+
+use rand::Rng;
+use ratatui::{style::Color, widgets::canvas::Rectangle};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    collision::{overlaps, side_of},
+    court::Court,
+    world::{Kind, World},
+};
+pub use crate::collision::Side;
+
+pub const COLUMNS: usize = 15;
+pub const MAX_ROWS: usize = 8;
+
+const BRICK_WIDTH: f64 = 10.0;
+const BRICK_HEIGHT: f64 = 4.0;
+const LEFT: f64 = Court::FULL.left(); //the bricks span the full court
+const TOP_ROW_Y: f64 = Court::FULL.top() - 22.0; //rows stack downwards from here, clear of both paddles
+
+const ROW_COLORS: [Color; 4] = [Color::Green, Color::Blue, Color::Magenta, Color::Cyan];
+
+const ZONES_FROM: u32 = 3; //the first generated stage with zones
+const MAX_ZONES: usize = 3;
+const ZONES: Range<f64> = Court::FULL.bottom() + 15.0..Court::FULL.bottom() + 35.0; //where zones start up the court
+const LIFT: f64 = 0.5; //court units a tick the lift pocket pushes the ball towards the cpu
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BrickKind {
+    Breakable,
+    Obstacle,
+}
+
+#[derive(Clone)]
+pub struct Brick {
+    pub kind: BrickKind,
+    pub row: usize,
+    pub col: usize,
+    pub rect: Rectangle,
+}
+
+// Stretches of court that change how the ball moves while it is inside them
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ZoneKind {
+    Boost, //speed-up strip
+    Slow,
+    Lift, //reverse gravity, pushes the ball back up the court
+}
+
+impl ZoneKind {
+    const NAMES: [(&'static str, ZoneKind); 3] = [("boost", ZoneKind::Boost), ("slow", ZoneKind::Slow), ("lift", ZoneKind::Lift)];
+
+    fn name(self) -> &'static str {
+        ZoneKind::NAMES.iter().find(|(_, kind)| *kind == self).map(|(name, _)| *name).unwrap_or("boost")
+    }
+
+    // How fast the ball travels inside, compared to outside
+    pub fn pace(self) -> f64 {
+        match self {
+            ZoneKind::Boost => 1.6,
+            ZoneKind::Slow => 0.5,
+            ZoneKind::Lift => 1.0,
+        }
+    }
+
+    // Court units a tick the ball is pushed up the court on top of its own travel
+    pub fn lift(self) -> f64 {
+        if self == ZoneKind::Lift { LIFT } else { 0.0 }
+    }
+}
+
+#[derive(Clone)]
+pub struct Zone {
+    pub kind: ZoneKind,
+    pub rect: Rectangle,
+}
+
+#[derive(Clone)]
+pub struct Level {
+    pub index: u32,
+    pub bricks: Vec<Brick>,
+    pub zones: Vec<Zone>,
+}
+
+type Grid = Vec<Vec<Option<BrickKind>>>;
+
+impl Level {
+    pub fn generate<R: Rng>(index: u32, rng: &mut R) -> Level {
+        let step = index.saturating_sub(1) as f64;
+        let rows = (2 + index as usize / 2).min(MAX_ROWS);
+        let density = (0.35 + 0.05 * step).min(0.8);
+        let obstacles = (0.04 * step).min(0.3);
+
+        loop {
+            let mut grid: Grid = vec![vec![None; COLUMNS]; rows];
+            for cell in grid.iter_mut().flatten() {
+                if rng.gen_bool(density) {
+                    *cell = Some(if rng.gen_bool(obstacles) {
+                        BrickKind::Obstacle
+                    } else {
+                        BrickKind::Breakable
+                    });
+                }
+            }
+
+            if !is_solvable(&grid) {
+                carve(&mut grid, rng.gen_range(0..COLUMNS));
+            }
+            // a brick boxed in by obstacles could never be broken and the stage never cleared
+            while let Some(col) = walled_in(&grid) {
+                carve(&mut grid, col);
+            }
+
+            let mut level = Level::from_grid(index, &grid);
+            if !level.cleared() {
+                if index >= ZONES_FROM {
+                    let zones = ((index - ZONES_FROM) as usize / 2 + 1).min(MAX_ZONES);
+                    level.zones = (0..zones).map(|_| random_zone(rng)).collect();
+                }
+                return level;
+            }
+        }
+    }
+
+    pub fn empty() -> Level {
+        Level {
+            index: 1,
+            bricks: Vec::new(),
+            zones: Vec::new(),
+        }
+    }
+
+    fn from_grid(index: u32, grid: &Grid) -> Level {
+        let mut level = Level { index, bricks: Vec::new(), zones: Vec::new() };
+        for (row, cells) in grid.iter().enumerate() {
+            for (col, cell) in cells.iter().enumerate() {
+                level.set(row, col, *cell);
+            }
+        }
+        level
+    }
+
+    // Level files hold one line per row, '.' for empty, '#' for a brick and 'X' for an obstacle,
+    // then a line 'zone <boost|slow|lift> <x> <y> <width> <height>' in court units per zone
+    pub fn parse(text: &str) -> Result<Level, String> {
+        let mut grid: Grid = Vec::new();
+        let mut zones = Vec::new();
+        for (n, line) in text.lines().map(str::trim_end).enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(zone) = line.strip_prefix("zone ") {
+                zones.push(parse_zone(zone).map_err(|err| format!("line {}: {}", n + 1, err))?);
+                continue;
+            }
+            if grid.len() == MAX_ROWS {
+                return Err(format!("more than {} rows", MAX_ROWS));
+            }
+            if line.chars().count() != COLUMNS {
+                return Err(format!("line {}: expected {} columns", n + 1, COLUMNS));
+            }
+            let row = line
+                .chars()
+                .map(|c| match c {
+                    '.' => Ok(None),
+                    '#' => Ok(Some(BrickKind::Breakable)),
+                    'X' => Ok(Some(BrickKind::Obstacle)),
+                    _ => Err(format!("line {}: unexpected '{}'", n + 1, c)),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            grid.push(row);
+        }
+        let mut level = Level::from_grid(1, &grid);
+        level.zones = zones;
+        Ok(level)
+    }
+
+    pub fn load(path: &Path) -> io::Result<Level> {
+        let text = fs::read_to_string(path)?;
+        Level::parse(&text).map_err(|err| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", path.display(), err))
+        })
+    }
+
+    pub fn to_text(&self) -> String {
+        let rows = self.bricks.iter().map(|b| b.row + 1).max().unwrap_or(0);
+        let mut text = String::new();
+        for row in 0..rows {
+            for col in 0..COLUMNS {
+                text.push(match self.get(row, col) {
+                    None => '.',
+                    Some(BrickKind::Breakable) => '#',
+                    Some(BrickKind::Obstacle) => 'X',
+                });
+            }
+            text.push('\n');
+        }
+        for zone in &self.zones {
+            let rect = &zone.rect;
+            text.push_str(&format!("zone {} {} {} {} {}\n", zone.kind.name(), rect.x, rect.y, rect.width, rect.height));
+        }
+        text
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+
+    pub fn get(&self, row: usize, col: usize) -> Option<BrickKind> {
+        self.bricks
+            .iter()
+            .find(|b| b.row == row && b.col == col)
+            .map(|b| b.kind)
+    }
+
+    pub fn set(&mut self, row: usize, col: usize, kind: Option<BrickKind>) {
+        self.bricks.retain(|b| b.row != row || b.col != col);
+        if let Some(kind) = kind {
+            let rect = Rectangle {
+                color: match kind {
+                    BrickKind::Breakable => ROW_COLORS[row % ROW_COLORS.len()],
+                    BrickKind::Obstacle => Color::DarkGray,
+                },
+                ..cell_rect(row, col)
+            };
+            self.bricks.push(Brick { kind, row, col, rect });
+        }
+    }
+
+    pub fn cleared(&self) -> bool {
+        !self.bricks.iter().any(|b| b.kind == BrickKind::Breakable)
+    }
+
+    // The zone the middle of the ball is in, the first one listed where they overlap
+    pub fn zone_at(&self, ball: &Rectangle) -> Option<ZoneKind> {
+        let (x, y) = (ball.x + ball.width / 2.0, ball.y + ball.height / 2.0);
+        self.zones
+            .iter()
+            .find(|zone| {
+                let rect = &zone.rect;
+                x >= rect.x && x < rect.x + rect.width && y >= rect.y && y < rect.y + rect.height
+            })
+            .map(|zone| zone.kind)
+    }
+
+    // Hands the bricks over to the world, which plays them from then on. The level keeps
+    // its index and zones
+    pub fn lay_out(&mut self, world: &mut World) {
+        for brick in self.bricks.drain(..) {
+            world.spawn(Kind::Brick(brick.kind), brick.rect, None);
+        }
+    }
+
+    // The level with the bricks still standing in the world, e.g. to save
+    pub fn standing(&self, world: &World) -> Level {
+        let mut level = self.clone();
+        for kind in [BrickKind::Breakable, BrickKind::Obstacle] {
+            for rect in world.of(Kind::Brick(kind)) {
+                if let Some((row, col)) = cell_of(rect) {
+                    level.set(row, col, Some(kind));
+                }
+            }
+        }
+        level
+    }
+}
+
+// Checks the ball against every brick in the world, knocking out the first breakable one it
+// touches. Returns the face it hit and whether the brick broke
+pub fn hit(world: &mut World, ball: &Rectangle) -> Option<(Side, bool)> {
+    let (brick, kind) = world.entities().find(|&(entity, kind)| matches!(kind, Kind::Brick(_)) && overlaps(&world[entity], ball))?;
+    let rect = &world[brick];
+    let side = side_of(rect, ball);
+    if let Some((row, col)) = cell_of(rect) {
+        log::debug!("ball hit brick at row {} col {}", row, col);
+    }
+    let broken = kind == Kind::Brick(BrickKind::Breakable);
+    if broken {
+        world.despawn(brick);
+    }
+    Some((side, broken))
+}
+
+// Takes every brick off the court
+pub fn clear(world: &mut World) {
+    for kind in [BrickKind::Breakable, BrickKind::Obstacle] {
+        world.despawn_all(Kind::Brick(kind));
+    }
+}
+
+pub fn cell_rect(row: usize, col: usize) -> Rectangle {
+    Rectangle {
+        x: LEFT + col as f64 * BRICK_WIDTH + 0.5,
+        y: TOP_ROW_Y - row as f64 * (BRICK_HEIGHT + 1.0),
+        width: BRICK_WIDTH - 1.0,
+        height: BRICK_HEIGHT,
+        color: Color::Reset,
+    }
+}
+
+fn parse_zone(text: &str) -> Result<Zone, String> {
+    let mut words = text.split_whitespace();
+    let name = words.next().ok_or("zone needs a kind")?;
+    let kind = ZoneKind::NAMES
+        .iter()
+        .find(|(known, _)| *known == name)
+        .map(|(_, kind)| *kind)
+        .ok_or_else(|| format!("unknown zone '{}'", name))?;
+    let numbers = words.map(str::parse).collect::<Result<Vec<f64>, _>>().map_err(|_| "invalid zone size")?;
+    let [x, y, width, height] = numbers[..] else {
+        return Err(String::from("zone needs x, y, width and height"));
+    };
+    Ok(Zone {
+        kind,
+        rect: Rectangle { x, y, width, height, color: Color::Reset },
+    })
+}
+
+// Somewhere in the open middle of the court, below the lowest row of bricks and clear
+// of the player's paddle
+fn random_zone<R: Rng>(rng: &mut R) -> Zone {
+    let kind = ZoneKind::NAMES[rng.gen_range(0..ZoneKind::NAMES.len())].1;
+    let width: f64 = rng.gen_range(30.0..50.0);
+    let height: f64 = rng.gen_range(8.0..14.0);
+    Zone {
+        kind,
+        rect: Rectangle {
+            x: rng.gen_range(LEFT..LEFT + COLUMNS as f64 * BRICK_WIDTH - width).round(),
+            y: rng.gen_range(ZONES).round(),
+            width: width.round(),
+            height: height.round(),
+            color: Color::Reset,
+        },
+    }
+}
+
+// Grid cell under a point in canvas coordinates
+pub fn cell_at(x: f64, y: f64) -> Option<(usize, usize)> {
+    let col = ((x - LEFT) / BRICK_WIDTH).floor();
+    let row = ((TOP_ROW_Y + BRICK_HEIGHT + 1.0 - y) / (BRICK_HEIGHT + 1.0)).floor();
+    if col < 0.0 || row < 0.0 || col >= COLUMNS as f64 || row >= MAX_ROWS as f64 {
+        return None;
+    }
+    Some((row as usize, col as usize))
+}
+
+// Grid cell a brick stands in
+pub fn cell_of(brick: &Rectangle) -> Option<(usize, usize)> {
+    cell_at(brick.x + brick.width / 2.0, brick.y + brick.height / 2.0)
+}
+
+// Breakable bricks eventually give way, so only obstacles can wall off the paddle
+fn is_solvable(grid: &Grid) -> bool {
+    let seen = reached(grid, &[0]);
+    seen[grid.len() - 1].iter().any(|&seen| seen)
+}
+
+// The column of the first breakable brick the ball can't get to from either open end of
+// the rows, going round the obstacles
+fn walled_in(grid: &Grid) -> Option<usize> {
+    let seen = reached(grid, &[0, grid.len() - 1]);
+    grid.iter().zip(&seen).find_map(|(cells, seen)| {
+        (0..COLUMNS).find(|&col| cells[col] == Some(BrickKind::Breakable) && !seen[col])
+    })
+}
+
+// The cells the ball gets to around the obstacles, coming in from the given rows
+fn reached(grid: &Grid, from: &[usize]) -> Vec<Vec<bool>> {
+    let free = |row: usize, col: usize| grid[row][col] != Some(BrickKind::Obstacle);
+
+    let mut seen = vec![vec![false; COLUMNS]; grid.len()];
+    let mut queue: VecDeque<_> = from
+        .iter()
+        .flat_map(|&row| (0..COLUMNS).map(move |col| (row, col)))
+        .filter(|&(row, col)| free(row, col))
+        .collect();
+    for &(row, col) in &queue {
+        seen[row][col] = true;
+    }
+
+    while let Some((row, col)) = queue.pop_front() {
+        let neighbours = [
+            (row.wrapping_sub(1), col),
+            (row + 1, col),
+            (row, col.wrapping_sub(1)),
+            (row, col + 1),
+        ];
+        for (r, c) in neighbours {
+            if r < grid.len() && c < COLUMNS && !seen[r][c] && free(r, c) {
+                seen[r][c] = true;
+                queue.push_back((r, c));
+            }
+        }
+    }
+    seen
+}
+
+fn carve(grid: &mut Grid, col: usize) {
+    for row in grid.iter_mut() {
+        if row[col] == Some(BrickKind::Obstacle) {
+            row[col] = None;
+        }
+    }
+}
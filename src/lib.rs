@@ -0,0 +1,531 @@
+mod ai;
+mod app;
+mod boss;
+mod cast;
+#[cfg(feature = "twitch")]
+mod chat;
+mod collision;
+mod combo;
+mod config;
+mod court;
+mod debug;
+mod editor;
+mod error;
+mod events;
+mod history;
+mod input;
+mod instant;
+mod leaderboard;
+pub mod level;
+mod locale;
+mod logging;
+mod menu;
+mod modifiers;
+mod mods;
+mod palette;
+mod profile;
+mod progress;
+pub mod render;
+mod results;
+mod save;
+mod screen;
+mod screenshot;
+mod series;
+mod settings;
+mod signals;
+mod sim;
+mod sound;
+mod splits;
+mod summary;
+mod targets;
+mod telemetry;
+mod toast;
+mod ui;
+pub mod world;
+
+use crossterm::event::{
+    Event, EventStream, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEventKind,
+};
+use std::{
+    any::Any,
+    io,
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+
+use futures::StreamExt;
+use log::{debug, info, warn};
+use tokio::time as tokio_time;
+
+use app::{serve, Mode};
+use cast::{Cast, Recorded};
+use config::USAGE;
+use editor::Editor;
+use history::Replay;
+#[cfg(feature = "twitch")]
+use input::chat_inputs;
+use input::{apply_input, replay_inputs};
+use level::Level;
+use render::{enter_terminal, leave_terminal, Renderer, TerminalRenderer};
+use screen::Clock;
+use signals::{Signal, Signals};
+use telemetry::StatsStream;
+use ui::{canvas_point, fits};
+
+pub use app::App;
+pub use config::Config;
+pub use court::Court;
+pub use error::PongError;
+pub use events::{GameEvent, Paddle};
+pub use history::{Input, InputSource};
+pub use progress::LevelProgress;
+pub use series::Winner;
+pub use sim::Simulation;
+
+enum Outcome {
+    Quit,
+    Redraw,
+    Suspend,
+    Interrupt,
+    Ignored,
+}
+
+pub fn run() -> Result<(), PongError> {
+    let mut config = match Config::from_args(std::env::args().skip(1)) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, USAGE);
+            std::process::exit(2);
+        }
+    };
+    if config.help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    logging::init(config.log_level.as_deref()).map_err(|source| PongError::Logging { source })?;
+
+    if config.simulate {
+        let seed = config.seed.unwrap_or_else(rand::random);
+        let batch = sim::simulate(&config, seed);
+        if config.json_summary {
+            match serde_json::to_string(&batch) {
+                Ok(json) => println!("{}", json),
+                Err(err) => eprintln!("can't write the results: {}", err),
+            }
+        } else {
+            println!("{}", batch.report(&config));
+        }
+        return Ok(());
+    }
+
+    let replay = match &config.replay {
+        Some(path) => Some(Replay::load(path).map_err(|source| PongError::Replay { path: path.clone(), source })?),
+        None => None,
+    };
+    if let Some(replay) = &replay {
+        config = Config::from_args(replay.args.iter().cloned()).map_err(PongError::ReplayArgs)?;
+        config.seed = Some(replay.seed);
+    }
+
+    let mut app = App::new(&config);
+    let replay_releases = replay.as_ref().map(|replay| replay.releases);
+    app.replay = replay.map(|replay| replay.inputs);
+    if let Some(path) = &config.level {
+        let level = Level::load(path).map_err(|source| PongError::Level { path: path.clone(), source })?;
+        app.set_level(Some(level.clone()));
+        app.custom_level = Some(level);
+    }
+    if let Some(path) = &config.stats_file {
+        let stats = StatsStream::create(path).map_err(|source| PongError::Stats { path: path.clone(), source })?;
+        app.stats = Some(stats);
+    }
+    #[cfg(feature = "twitch")]
+    if let Some(channel) = &config.twitch {
+        let server = config.irc_server.as_deref().unwrap_or(chat::TWITCH_SERVER);
+        let chat = chat::Chat::connect(server, channel).map_err(|source| PongError::Chat {
+            server: server.to_string(),
+            source,
+        })?;
+        app.chat = Some(chat);
+    }
+    if let Some(path) = &config.edit {
+        let editor = Editor::open(path, &app.locale).map_err(|source| PongError::Editor { path: path.clone(), source })?;
+        app.editor = Some(editor);
+        app.set_mode(Mode::Edit);
+    }
+
+    // put the terminal back before the panic message is printed
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = leave_terminal();
+        default_hook(info);
+    }));
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .map_err(PongError::Runtime)?;
+
+    let cast = match &config.record_cast {
+        Some(path) => Some(Cast::create(path).map_err(|source| PongError::Cast { path: path.clone(), source })?),
+        None => None,
+    };
+
+    // setup terminal, a replay holds keys the way the recorded terminal did. If that fails
+    // halfway the terminal is put back before saying so
+    let output = Recorded::new(io::stdout(), cast);
+    let setup = enter_terminal().and_then(|releases| Ok((releases, TerminalRenderer::new(output)?)));
+    let (releases, mut renderer) = setup.map_err(|err| {
+        let _ = leave_terminal();
+        PongError::Terminal(err)
+    })?;
+    app.key_releases = replay_releases.unwrap_or(releases);
+    app.history.releases = app.key_releases;
+
+    // run the app
+    let tick_rate = app::TICK;
+    let res = panic::catch_unwind(AssertUnwindSafe(|| {
+        runtime.block_on(run_app(&mut renderer, &mut app, tick_rate))
+    }));
+
+    // restore terminal
+    leave_terminal().and_then(|()| renderer.terminal.show_cursor()).map_err(PongError::Terminal)?;
+
+    let crash = match res {
+        Ok(Ok(())) => None,
+        Ok(Err(err)) => {
+            let err = PongError::Game(err);
+            eprintln!("{}", err);
+            Some(err.to_string())
+        }
+        Err(payload) => Some(format!("panic: {}", panic_message(payload.as_ref()))),
+    };
+    if let Some(reason) = crash {
+        match app.history.write_dump(&reason) {
+            Ok(path) => eprintln!("crash dump written to {}", path.display()),
+            Err(err) => eprintln!("could not write crash dump: {}", err),
+        }
+        std::process::exit(1);
+    }
+    if !config.quiet {
+        let summary = app.summary().or_else(|| app.last_game.take());
+        if config.json_summary {
+            match serde_json::to_string(&summary) {
+                Ok(json) => println!("{}", json),
+                Err(err) => eprintln!("can't write the summary: {}", err),
+            }
+        } else if let Some(summary) = summary {
+            for line in summary.lines(&app.locale) {
+                println!("{}", line);
+            }
+        }
+    }
+    if let Some(code) = app.exit_code {
+        std::process::exit(code);
+    }
+
+    Ok(())
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("unknown panic")
+    }
+}
+
+// Most steps run to catch up before the next frame. A game further behind than that
+// stretches each step over more ticks, up to MAX_TICK_SCALE, the ball moving further than
+// that each step could pass through a paddle. Whatever is still left over waits for the
+// next wake-up, so the game keeps its speed rather than never getting round to drawing
+const MAX_STEPS: u32 = 5;
+const MAX_TICK_SCALE: u32 = 2;
+
+// A stall this long, a suspended process or a machine waking up, is let go rather than
+// played through in a rush
+const MAX_LAG: Duration = Duration::from_secs(1);
+
+// The wall clock time not yet played through, which the game works off a whole tick at a
+// time. Every tick covers the same ground however long the frames in between take, a slow
+// frame is made up for with more steps before the next and a game that keeps falling
+// behind with longer ones
+struct Timestep {
+    tick: Duration,
+    since: Option<Instant>, //when the clock was last read, None while it stands still
+    lag: Duration,
+}
+
+impl Timestep {
+    fn new(tick: Duration) -> Timestep {
+        Timestep {
+            tick,
+            since: None,
+            lag: Duration::ZERO,
+        }
+    }
+
+    // Paused screens stop the clock, it starts a whole tick away again
+    fn stop(&mut self) {
+        self.since = None;
+        self.lag = Duration::ZERO;
+    }
+
+    // When the next tick is due, starting the clock if it stood still
+    fn due(&mut self, now: Instant) -> Instant {
+        let since = *self.since.get_or_insert(now);
+        since + self.tick.saturating_sub(self.lag)
+    }
+
+    // The steps to run by now and how many ticks each covers, the time they don't make up
+    // stays on the clock for the next
+    fn steps(&mut self, now: Instant) -> (u32, u16) {
+        let since = self.since.replace(now).unwrap_or(now);
+        self.lag += now.saturating_duration_since(since);
+        if self.lag > MAX_LAG {
+            info!("stalled for {:?}, carrying on from here", self.lag);
+            self.lag = self.tick;
+        }
+        let due = (self.lag.as_nanos() / self.tick.as_nanos()) as u32;
+        let scale = due.div_ceil(MAX_STEPS).clamp(1, MAX_TICK_SCALE);
+        let steps = (due / scale).min(MAX_STEPS);
+        if scale > 1 {
+            debug!("{} ticks behind, playing {} of {} ticks each", due, steps, scale);
+        }
+        self.lag -= self.tick * steps * scale;
+        (steps, scale as u16)
+    }
+}
+
+// Redraw cap in low bandwidth mode, ticks carry on at the normal rate
+const LOW_BANDWIDTH_FRAME: Duration = Duration::from_millis(100);
+
+// Least time between the start of two draws, None draws as soon as anything changes
+fn frame_interval(app: &App) -> Option<Duration> {
+    let cap = (app.max_fps > 0).then(|| Duration::from_secs_f64(1.0 / app.max_fps as f64));
+    match cap {
+        _ if !app.low_bandwidth => cap,
+        Some(cap) => Some(cap.max(LOW_BANDWIDTH_FRAME)),
+        None => Some(LOW_BANDWIDTH_FRAME),
+    }
+}
+
+async fn run_app<R: Renderer>(
+    renderer: &mut R,
+    app: &mut App,
+    tick_rate: Duration,
+) -> io::Result<()> {
+    let mut events = EventStream::new();
+    let mut signals = Signals::listen();
+    let mut timestep = Timestep::new(tick_rate);
+    let mut dirty = true; //only redraw after a tick, an input or a resize
+    let mut drawn_at: Option<Instant> = None; //when the last frame was due
+
+    serve(app);
+
+    loop {
+        // changes that come in faster than the cap wait for the next frame, which is slept
+        // for on its own rather than folded into waiting for input
+        let frame = frame_interval(app);
+        let throttle = drawn_at.zip(frame).map(|(at, frame)| at + frame);
+        if app.toasts.update(Instant::now()) {
+            dirty = true;
+        }
+        let held = dirty && matches!(throttle, Some(at) if Instant::now() < at);
+        if dirty && !held {
+            let draw_start = Instant::now();
+            renderer.render(app)?;
+            app.debug.draw(draw_start.elapsed());
+            app.debug.frame(renderer.stats());
+            // keep to the frame grid when only just late, so the rate doesn't drift below the cap
+            drawn_at = Some(match throttle {
+                Some(due) if frame.is_some_and(|frame| draw_start < due + frame) => due,
+                _ => draw_start,
+            });
+            dirty = false;
+
+            if std::mem::take(&mut app.screenshot) {
+                if let Some(frame) = renderer.screenshot() {
+                    let path = app.file(&screenshot::file_name());
+                    let notice = match screenshot::save(&frame, &path) {
+                        Ok(()) => app.locale.format("notice.screenshot", &[&path.display()]),
+                        Err(err) => app.locale.format("notice.screenshot_failed", &[&err]),
+                    };
+                    info!("{}", notice);
+                    app.post_notice(notice);
+                    dirty = true;
+                    continue; //the next frame shows the toast
+                }
+            }
+
+            if !app.low_bandwidth && app.debug.slow_link() {
+                info!("drawing takes {:?}, switching to low bandwidth mode", app.debug.draw_time);
+                app.low_bandwidth = true;
+            }
+        }
+        let redraw_at = tokio_time::Instant::from_std(throttle.unwrap_or_else(Instant::now));
+
+        if app.replay.is_some() {
+            if replay_inputs(app) {
+                return Ok(());
+            }
+            dirty = true;
+        }
+
+        // the game holds still while the terminal is too small and picks up again after a resize
+        let clock = if app.help { Clock::Still } else { app.screen().clock(app) };
+        let ticking = clock == Clock::Game && fits(renderer.size()?);
+        let animating = clock == Clock::Animation;
+        let running = ticking || animating;
+        if !running {
+            timestep.stop();
+        }
+        let tick_at = tokio_time::Instant::from_std(if running { timestep.due(Instant::now()) } else { Instant::now() });
+        let awaiting_demo = app.mode() == Mode::Menu && !app.help && app.replay.is_none();
+        let demo_at = tokio_time::Instant::from_std(app.menu.demo_at());
+        let toast_shown = app.toasts.current().is_some();
+        let toast_due = tokio_time::Instant::from_std(app.toasts.due().unwrap_or_else(Instant::now));
+
+        let wait_start = Instant::now();
+        tokio::select! {
+            event = events.next() => {
+                app.debug.poll_latency = wait_start.elapsed();
+                let event = match event {
+                    Some(event) => event?,
+                    None => return Ok(()),
+                };
+                match handle_event(renderer, app, event)? {
+                    Outcome::Quit => return Ok(()),
+                    Outcome::Redraw => dirty = true,
+                    Outcome::Suspend => {
+                        info!("suspending");
+                        renderer.suspend()?;
+                        dirty = true;
+                    }
+                    Outcome::Interrupt => {
+                        interrupted(app, Signal::Interrupt);
+                        return Ok(());
+                    }
+                    Outcome::Ignored => {}
+                }
+            }
+            signal = signals.next() => match signal {
+                Signal::Suspend => {
+                    info!("suspended by signal");
+                    renderer.suspend()?;
+                    dirty = true;
+                }
+                Signal::Interrupt | Signal::Terminate => {
+                    interrupted(app, signal);
+                    return Ok(());
+                }
+            },
+            _ = tokio_time::sleep_until(tick_at), if running => {
+                if ticking {
+                    app.debug.poll_latency = wait_start.elapsed();
+                }
+                let (steps, scale) = timestep.steps(Instant::now());
+                app.tick_scale = scale;
+                for _ in 0..steps {
+                    // a tick that pauses or ends the game leaves the rest unplayed
+                    if app.screen().clock(app) != clock {
+                        break;
+                    }
+                    // a replay's inputs come in at the step they were recorded on
+                    if app.replay.is_some() && replay_inputs(app) {
+                        return Ok(());
+                    }
+                    let tick_start = Instant::now();
+                    app.screen().update(app);
+                    if ticking {
+                        app.debug.tick_time = tick_start.elapsed();
+                    }
+                    #[cfg(feature = "twitch")]
+                    chat_inputs(app);
+                }
+                app.debug.steps = steps;
+                dirty = true;
+            }
+            _ = tokio_time::sleep_until(demo_at), if awaiting_demo => {
+                apply_input(app, Input::Demo);
+                dirty = true;
+            }
+            _ = tokio_time::sleep_until(toast_due), if toast_shown => {} //takes it down
+            _ = tokio_time::sleep_until(redraw_at), if held => {}
+        }
+    }
+}
+
+// Saves a game in progress so Continue picks it up, run restores the terminal and exits
+// with the signal's code
+fn interrupted(app: &mut App, signal: Signal) {
+    info!("{:?}, shutting down", signal);
+    if app.mode() == Mode::InstantReplay {
+        app.stop_instant_replay(); //the live court goes in the save, not the replayed one
+    }
+    if app.in_progress() {
+        match save::save(app) {
+            Ok(()) => info!("saved the game at tick {}", app.tick_count),
+            Err(err) => warn!("can't save the game: {}", err),
+        }
+    }
+    app.exit_code = Some(signal.exit_code());
+}
+
+fn handle_event<R: Renderer>(
+    renderer: &R,
+    app: &mut App,
+    event: Event,
+) -> io::Result<Outcome> {
+    let outcome = match event {
+        // a release only ends a held key, Windows and the enhanced keyboard protocol send them
+        Event::Key(key) if key.kind == KeyEventKind::Release => {
+            if app.replay.is_none() {
+                apply_input(app, Input::Release(key.code));
+            }
+            Outcome::Ignored
+        }
+        // raw mode keeps the terminal from turning Ctrl+Z into SIGTSTP, so it is done here
+        Event::Key(key) if key.code == KeyCode::Char('z') && key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Outcome::Suspend
+        }
+        Event::Key(key) if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) => {
+            Outcome::Interrupt
+        }
+        // live input is ignored while a replay drives the game, except to quit
+        Event::Key(key) if app.replay.is_some() => {
+            if key.code == KeyCode::Char('q') {
+                Outcome::Quit
+            } else {
+                Outcome::Ignored
+            }
+        }
+        Event::Key(key) => {
+            if apply_input(app, Input::Key(key.code)) {
+                Outcome::Quit
+            } else {
+                Outcome::Redraw
+            }
+        }
+        Event::Mouse(mouse) if app.mode() == Mode::Edit && app.replay.is_none() => {
+            let size = renderer.size()?;
+            match (mouse.kind, canvas_point(size, mouse.column, mouse.row)) {
+                (MouseEventKind::Down(MouseButton::Left), Some((x, y))) => {
+                    apply_input(app, Input::Click(x, y));
+                    Outcome::Redraw
+                }
+                _ => Outcome::Ignored,
+            }
+        }
+        Event::FocusGained | Event::FocusLost if app.replay.is_none() => {
+            apply_input(app, Input::Focus(matches!(event, Event::FocusGained)));
+            Outcome::Redraw
+        }
+        Event::Resize(..) => Outcome::Redraw,
+        _ => Outcome::Ignored,
+    };
+    Ok(outcome)
+}
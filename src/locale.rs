@@ -0,0 +1,77 @@
+use std::{collections::HashMap, env, fmt::Display, fs, path::Path};
+
+use log::{info, warn};
+
+pub const LOCALES_DIR: &str = "locales";
+
+const ENGLISH: &str = include_str!("../locales/en.json");
+// translations that ship inside the binary, a file in locales/ takes precedence
+const BUILT_IN: [(&str, &str); 2] = [("en", ENGLISH), ("de", include_str!("../locales/de.json"))];
+
+// User facing text by key, anything a translation leaves out comes from English
+pub struct Locale {
+    strings: HashMap<String, String>,
+    english: HashMap<String, String>,
+}
+
+impl Locale {
+    pub fn english() -> Locale {
+        Locale {
+            strings: HashMap::new(),
+            english: serde_json::from_str(ENGLISH).expect("built-in English locale"),
+        }
+    }
+
+    // `lang` is a code like 'de' or a LANG value like 'de_DE.UTF-8', without one the
+    // LANG environment variable decides
+    pub fn load(lang: Option<&str>) -> Locale {
+        let mut locale = Locale::english();
+        let lang = match lang.map(String::from).or_else(|| env::var("LANG").ok()) {
+            Some(lang) => lang,
+            None => return locale,
+        };
+        let code = lang.split(['_', '.', '-']).next().unwrap_or("").to_lowercase();
+        if code.is_empty() || code == "c" || code == "posix" {
+            return locale;
+        }
+
+        let path = Path::new(LOCALES_DIR).join(format!("{}.json", code));
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(_) => match BUILT_IN.iter().find(|(name, _)| *name == code) {
+                Some((_, text)) => text.to_string(),
+                None => {
+                    info!("no locale for '{}', using English", lang);
+                    return locale;
+                }
+            },
+        };
+        match serde_json::from_str(&text) {
+            Ok(strings) => locale.strings = strings,
+            Err(err) => warn!("locale '{}' not loaded: {}", code, err),
+        }
+        locale
+    }
+
+    pub fn text(&self, key: &str) -> String {
+        self.strings
+            .get(key)
+            .or_else(|| self.english.get(key))
+            .cloned()
+            .unwrap_or_else(|| key.to_string())
+    }
+
+    // Fills each {} in the text with the next argument
+    pub fn format(&self, key: &str, args: &[&dyn Display]) -> String {
+        let text = self.text(key);
+        let mut parts = text.split("{}");
+        let mut out = parts.next().unwrap_or("").to_string();
+        for (i, part) in parts.enumerate() {
+            if let Some(arg) = args.get(i) {
+                out.push_str(&arg.to_string());
+            }
+            out.push_str(part);
+        }
+        out
+    }
+}
@@ -0,0 +1,23 @@
+use std::{env, fs::OpenOptions, io};
+
+use env_logger::{Builder, Target};
+
+pub const LOG_FILE: &str = "pong_terminal.log";
+
+// Stdout is the game screen, so log records only ever go to the log file.
+// Nothing is written unless `--log-level` or RUST_LOG asks for it.
+pub fn init(level: Option<&str>) -> io::Result<()> {
+    let spec = match level.map(String::from).or_else(|| env::var("RUST_LOG").ok()) {
+        Some(spec) => spec,
+        None => return Ok(()),
+    };
+
+    let file = OpenOptions::new().create(true).append(true).open(LOG_FILE)?;
+    Builder::new()
+        .parse_filters(&spec)
+        .target(Target::Pipe(Box::new(file)))
+        .init();
+
+    log::info!("logging started with filter '{}'", spec);
+    Ok(())
+}
@@ -0,0 +1,80 @@
+use std::time::{Duration, Instant};
+
+const DEMO_DELAY: Duration = Duration::from_secs(15);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Item {
+    Continue,
+    Play,
+    Procedural,
+    Settings,
+    Profile,
+    Quit,
+}
+
+const ITEMS: [Item; 5] = [Item::Play, Item::Procedural, Item::Settings, Item::Profile, Item::Quit];
+
+impl Item {
+    // Locale key of the label
+    pub fn key(self) -> &'static str {
+        match self {
+            Item::Continue => "menu.continue",
+            Item::Play => "menu.play",
+            Item::Procedural => "menu.procedural",
+            Item::Settings => "menu.settings",
+            Item::Profile => "menu.profile",
+            Item::Quit => "menu.quit",
+        }
+    }
+}
+
+pub struct Menu {
+    pub selected: usize,
+    pub frame: u64, //ticks spent on the menu, drives the logo animation
+    items: Vec<Item>,
+    idle_since: Instant,
+}
+
+impl Menu {
+    pub fn new() -> Menu {
+        Menu {
+            selected: 0,
+            frame: 0,
+            items: ITEMS.to_vec(),
+            idle_since: Instant::now(),
+        }
+    }
+
+    pub fn items(&self) -> &[Item] {
+        &self.items
+    }
+
+    // Puts Continue at the top once there is a saved game to go back to
+    pub fn offer_continue(&mut self) {
+        if !self.items.contains(&Item::Continue) {
+            self.items.insert(0, Item::Continue);
+            self.selected = 0;
+        }
+    }
+
+    pub fn up(&mut self) {
+        self.selected = (self.selected + self.items.len() - 1) % self.items.len();
+    }
+
+    pub fn down(&mut self) {
+        self.selected = (self.selected + 1) % self.items.len();
+    }
+
+    pub fn item(&self) -> Item {
+        self.items[self.selected]
+    }
+
+    pub fn touch(&mut self) {
+        self.idle_since = Instant::now();
+    }
+
+    // When the attract mode kicks in if nothing is pressed
+    pub fn demo_at(&self) -> Instant {
+        self.idle_since + DEMO_DELAY
+    }
+}
@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use crate::{court::Court, locale::Locale};
+
+pub const NAMES: [&str; 4] = ["mirror", "blink", "walls", "shadow"];
+
+const BLINK_SHOWN: u64 = 60; //ticks the ball stays in sight between blinks
+const BLINK_HIDDEN: u64 = 40;
+pub const BLINK_NEAR: f64 = 10.0; //a hidden ball still shows this close to a wall or paddle
+const WALL_STEP: u16 = 5; //each wall moves in this much at every level up
+const MIN_COURT_WIDTH: u16 = 60;
+
+// Optional rules that make a match harder, picked in the settings before it starts and
+// kept for the whole match. Runs on the leaderboard say which were on
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Modifiers {
+    pub mirror: bool, //left moves the paddle right and the other way round
+    pub blink: bool,  //the ball disappears for a moment now and then
+    pub walls: bool,  //the side walls close in a little every level
+    pub shadow: bool, //a harmless decoy ball mirrors the real one across the court
+}
+
+impl Modifiers {
+    // A comma separated list of names, e.g. 'mirror,walls'
+    pub fn parse(list: &str) -> Result<Modifiers, String> {
+        let mut modifiers = Modifiers::default();
+        for name in list.split(',').map(str::trim).filter(|name| !name.is_empty()) {
+            match name {
+                "mirror" => modifiers.mirror = true,
+                "blink" => modifiers.blink = true,
+                "walls" => modifiers.walls = true,
+                "shadow" => modifiers.shadow = true,
+                _ => return Err(format!("unknown modifier '{}', pick from {}", name, NAMES.join(", "))),
+            }
+        }
+        Ok(modifiers)
+    }
+
+    pub fn any(&self) -> bool {
+        self.mirror || self.blink || self.walls || self.shadow
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        [self.mirror, self.blink, self.walls, self.shadow]
+            .into_iter()
+            .zip(NAMES)
+            .filter_map(|(on, name)| on.then_some(name))
+            .collect()
+    }
+
+    // The names as the interface shows them, e.g. for the court title
+    pub fn describe(&self, locale: &Locale) -> String {
+        let names: Vec<String> = self.names().iter().map(|name| locale.text(&format!("modifier.{}", name))).collect();
+        names.join(", ")
+    }
+}
+
+// Timer of the blink modifier, the ball is seen for a while and then gone for a while and
+// the player has to remember where it was heading
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Visibility {
+    pub visible: bool,
+    left: u64, //ticks until it changes
+}
+
+impl Visibility {
+    pub fn new() -> Visibility {
+        Visibility {
+            visible: true,
+            left: BLINK_SHOWN,
+        }
+    }
+
+    pub fn tick(&mut self, scale: u64) {
+        self.left = self.left.saturating_sub(scale);
+        if self.left == 0 {
+            self.visible = !self.visible;
+            self.left = if self.visible { BLINK_SHOWN } else { BLINK_HIDDEN };
+        }
+    }
+}
+
+// The playground a step narrower, unless that would take it under MIN_COURT_WIDTH
+pub fn narrowed(playground: Court) -> Court {
+    if playground.width() < (MIN_COURT_WIDTH + 2 * WALL_STEP) as f64 {
+        playground
+    } else {
+        playground.inset(WALL_STEP)
+    }
+}
@@ -0,0 +1,173 @@
+#[cfg(feature = "scripting")]
+use std::{fs, path::Path};
+
+#[cfg(feature = "scripting")]
+use log::{info, warn};
+
+use crate::events::GameEvent;
+
+pub const MODS_DIR: &str = "mods";
+
+// The part of the game a mod may read and change, copied in and out around each hook
+#[cfg_attr(not(feature = "scripting"), allow(dead_code))]
+pub struct Tuning {
+    pub tick: u64,
+    pub score: u16,
+    pub vx: f64,
+    pub vy: f64,
+    pub max_speed: f64,
+    pub paddle_width: f64,
+    pub hud: String,
+}
+
+// Every mods/*.rhai script, each may define on_tick() and on_event(name) and works on `this`:
+//     fn on_event(name) { if name == "LevelUp" { this.paddle_width -= 1.0; this.hud = "ouch"; } }
+#[cfg(feature = "scripting")]
+pub struct Mods {
+    engine: rhai::Engine,
+    scripts: Vec<Script>,
+}
+
+#[cfg(feature = "scripting")]
+struct Script {
+    name: String,
+    ast: rhai::AST,
+    scope: rhai::Scope<'static>,
+}
+
+// Built without the scripting feature the mods directory is ignored
+#[cfg(not(feature = "scripting"))]
+pub struct Mods;
+
+#[cfg(feature = "scripting")]
+impl Mods {
+    pub fn none() -> Mods {
+        Mods {
+            engine: rhai::Engine::new(),
+            scripts: Vec::new(),
+        }
+    }
+
+    // A script that fails to compile is skipped, the rest still load
+    pub fn load(dir: &Path) -> Mods {
+        let mut mods = Mods::none();
+        let mut paths = match fs::read_dir(dir) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| path.extension().is_some_and(|ext| ext == "rhai"))
+                .collect::<Vec<_>>(),
+            Err(_) => return mods,
+        };
+        paths.sort();
+
+        for path in paths {
+            let name = path.display().to_string();
+            let mut scope = rhai::Scope::new();
+            let loaded = mods
+                .engine
+                .compile_file(path)
+                .and_then(|ast| mods.engine.run_ast_with_scope(&mut scope, &ast).map(|_| ast));
+            match loaded {
+                Ok(ast) => {
+                    info!("loaded mod {}", name);
+                    mods.scripts.push(Script { name, ast, scope });
+                }
+                Err(err) => warn!("can't load mod {}: {}", name, err),
+            }
+        }
+        mods
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scripts.is_empty()
+    }
+
+    // Runs on_event for each event of the tick, then on_tick. A mod that errors is unloaded
+    pub fn run(&mut self, events: &[GameEvent], tuning: &mut Tuning) {
+        let mut this = rhai::Dynamic::from_map(to_map(tuning));
+        let engine = &self.engine;
+        self.scripts.retain_mut(|script| {
+            let result = events
+                .iter()
+                .try_for_each(|event| script.call(engine, &mut this, "on_event", vec![event.name().into()]))
+                .and_then(|_| script.call(engine, &mut this, "on_tick", Vec::new()));
+            if let Err(err) = &result {
+                warn!("mod {} failed, unloading it: {}", script.name, err);
+            }
+            result.is_ok()
+        });
+        if let Some(map) = this.try_cast::<rhai::Map>() {
+            from_map(&map, tuning);
+        }
+    }
+}
+
+#[cfg(feature = "scripting")]
+impl Script {
+    fn call(
+        &mut self,
+        engine: &rhai::Engine,
+        this: &mut rhai::Dynamic,
+        hook: &str,
+        args: Vec<rhai::Dynamic>,
+    ) -> Result<(), Box<rhai::EvalAltResult>> {
+        if !self.ast.iter_functions().any(|f| f.name == hook && f.params.len() == args.len()) {
+            return Ok(());
+        }
+        let options = rhai::CallFnOptions::new().eval_ast(false).bind_this_ptr(this);
+        engine
+            .call_fn_with_options::<rhai::Dynamic>(options, &mut self.scope, &self.ast, hook, args)
+            .map(|_| ())
+    }
+}
+
+#[cfg(feature = "scripting")]
+fn to_map(tuning: &Tuning) -> rhai::Map {
+    let mut map = rhai::Map::new();
+    map.insert("tick".into(), (tuning.tick as rhai::INT).into());
+    map.insert("score".into(), (tuning.score as rhai::INT).into());
+    map.insert("vx".into(), tuning.vx.into());
+    map.insert("vy".into(), tuning.vy.into());
+    map.insert("max_speed".into(), tuning.max_speed.into());
+    map.insert("paddle_width".into(), tuning.paddle_width.into());
+    map.insert("hud".into(), tuning.hud.clone().into());
+    map
+}
+
+// Only the physics and the hud text are taken back, tick and score are for reading
+#[cfg(feature = "scripting")]
+fn from_map(map: &rhai::Map, tuning: &mut Tuning) {
+    let float = |key: &str| map.get(key).and_then(|value| value.as_float().ok());
+    if let Some(vx) = float("vx") {
+        tuning.vx = vx;
+    }
+    if let Some(vy) = float("vy") {
+        tuning.vy = vy;
+    }
+    if let Some(max_speed) = float("max_speed") {
+        tuning.max_speed = max_speed;
+    }
+    if let Some(width) = float("paddle_width") {
+        tuning.paddle_width = width;
+    }
+    if let Some(hud) = map.get("hud").and_then(|value| value.clone().into_string().ok()) {
+        tuning.hud = hud;
+    }
+}
+
+#[cfg(not(feature = "scripting"))]
+impl Mods {
+    pub fn none() -> Mods {
+        Mods
+    }
+
+    pub fn load(_dir: &std::path::Path) -> Mods {
+        Mods
+    }
+
+    pub fn is_empty(&self) -> bool {
+        true
+    }
+
+    pub fn run(&mut self, _events: &[GameEvent], _tuning: &mut Tuning) {}
+}
@@ -0,0 +1,156 @@
+use ratatui::style::Color;
+
+use crate::level::BrickKind;
+
+pub const PALETTES: [&str; 4] = ["default", "deuteranopia", "protanopia", "tritanopia"];
+
+// Okabe-Ito colours, told apart by every common form of colour blindness
+const ORANGE: Color = Color::Rgb(230, 159, 0);
+const SKY_BLUE: Color = Color::Rgb(86, 180, 233);
+const GREEN: Color = Color::Rgb(0, 158, 115);
+const YELLOW: Color = Color::Rgb(240, 228, 66);
+const BLUE: Color = Color::Rgb(0, 114, 178);
+const VERMILLION: Color = Color::Rgb(213, 94, 0);
+const PURPLE: Color = Color::Rgb(204, 121, 167);
+
+const INTENSITY_STEP: f32 = 0.04;
+
+// Every colour the court is drawn with
+#[derive(Clone, Copy, Debug)]
+pub struct Palette {
+    pub ball: Color,
+    pub decoy: Color, //the shadow modifier's ball, a dimmer shade of the real one
+    pub catchable: Color, //the ball once it is low enough for the paddle to reach
+    pub solid_catchable: bool, //also fill the ball in then, a cue that doesn't rely on colour
+    pub solid_obstacles: bool, //tells obstacles from bricks by shape as well
+    pub paddle: Color,
+    pub target: Color, //rings in target practice
+    pub boss: Color, //the boss paddle and the balls it drops
+    pub glow: Color, //the player's paddle and the combo meter while it pays out
+    pub bricks: [Color; 4],
+    pub obstacle: Color,
+    pub zone: Color, //the shading of court zones, whose kinds differ by pattern
+    pub score_track: Color,
+    pub win: Color,
+    pub lose: Color,
+    pub borders: [Color; 4], //the court's border, a step further every speed level
+    pub tints: [Color; 4], //and its background, dark enough for everything drawn over it
+}
+
+// How the court looks and sounds at a speed level
+pub struct Ambience {
+    pub border: Color,
+    pub tint: Color,
+    pub intensity: f32, //raises the pitch of the game's sound
+}
+
+const DEFAULT: Palette = Palette {
+    ball: Color::Red,
+    decoy: Color::Rgb(120, 30, 30),
+    catchable: Color::Yellow,
+    solid_catchable: false,
+    solid_obstacles: false,
+    paddle: Color::White,
+    target: Color::LightGreen,
+    boss: Color::LightRed,
+    glow: Color::LightYellow,
+    bricks: [Color::Green, Color::Blue, Color::Magenta, Color::Cyan],
+    obstacle: Color::DarkGray,
+    zone: Color::DarkGray,
+    score_track: Color::Red,
+    win: Color::Yellow,
+    lose: Color::Red,
+    borders: [Color::Reset, Color::Cyan, Color::Yellow, Color::LightRed],
+    tints: [Color::Reset, Color::Rgb(0, 10, 26), Color::Rgb(20, 14, 0), Color::Rgb(28, 0, 0)],
+};
+
+// red and green look alike, blue against orange and yellow keeps its contrast
+const RED_GREEN: Palette = Palette {
+    ball: SKY_BLUE,
+    decoy: Color::Rgb(43, 90, 117),
+    catchable: YELLOW,
+    solid_catchable: true,
+    solid_obstacles: false,
+    paddle: Color::White,
+    target: ORANGE,
+    boss: PURPLE,
+    glow: YELLOW,
+    bricks: [BLUE, ORANGE, PURPLE, YELLOW],
+    obstacle: Color::DarkGray,
+    zone: Color::DarkGray,
+    score_track: BLUE,
+    win: YELLOW,
+    lose: SKY_BLUE,
+    borders: [Color::Reset, SKY_BLUE, YELLOW, ORANGE],
+    tints: [Color::Reset, Color::Rgb(0, 12, 22), Color::Rgb(20, 19, 0), Color::Rgb(26, 16, 0)],
+};
+
+// blue and green, yellow and violet look alike, red against cyan does not
+const TRITANOPIA: Palette = Palette {
+    ball: VERMILLION,
+    decoy: Color::Rgb(107, 47, 0),
+    catchable: SKY_BLUE,
+    solid_catchable: true,
+    solid_obstacles: false,
+    paddle: Color::White,
+    target: GREEN,
+    boss: PURPLE,
+    glow: SKY_BLUE,
+    bricks: [VERMILLION, GREEN, PURPLE, Color::Gray],
+    obstacle: Color::DarkGray,
+    zone: Color::DarkGray,
+    score_track: VERMILLION,
+    win: SKY_BLUE,
+    lose: VERMILLION,
+    borders: [Color::Reset, SKY_BLUE, PURPLE, VERMILLION],
+    tints: [Color::Reset, Color::Rgb(0, 14, 20), Color::Rgb(22, 12, 18), Color::Rgb(24, 8, 0)],
+};
+
+// no colour at all, everything that matters shows up in the shapes
+pub const MONOCHROME: Palette = Palette {
+    ball: Color::Reset,
+    decoy: Color::Reset,
+    catchable: Color::Reset,
+    solid_catchable: true,
+    solid_obstacles: true,
+    paddle: Color::Reset,
+    target: Color::Reset,
+    boss: Color::Reset,
+    glow: Color::Reset,
+    bricks: [Color::Reset; 4],
+    obstacle: Color::Reset,
+    zone: Color::Reset,
+    score_track: Color::Reset,
+    win: Color::Reset,
+    lose: Color::Reset,
+    borders: [Color::Reset; 4],
+    tints: [Color::Reset; 4],
+};
+
+impl Palette {
+    // Names come from PALETTES, anything else gets the default colours
+    pub fn named(name: Option<&str>) -> Palette {
+        match name {
+            Some("deuteranopia" | "protanopia") => RED_GREEN,
+            Some("tritanopia") => TRITANOPIA,
+            _ => DEFAULT,
+        }
+    }
+
+    // The steps run out after the fourth level, which keeps the last one
+    pub fn ambience(&self, level: u64) -> Ambience {
+        let step = (level.saturating_sub(1) as usize).min(self.borders.len() - 1);
+        Ambience {
+            border: self.borders[step],
+            tint: self.tints[step],
+            intensity: 1.0 + INTENSITY_STEP * step as f32,
+        }
+    }
+
+    pub fn brick(&self, kind: BrickKind, row: usize) -> Color {
+        match kind {
+            BrickKind::Breakable => self.bricks[row % self.bricks.len()],
+            BrickKind::Obstacle => self.obstacle,
+        }
+    }
+}
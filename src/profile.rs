@@ -0,0 +1,166 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::series::Winner;
+
+pub const PROFILES_DIR: &str = "profiles";
+const PROFILE_FILE: &str = "profile.json";
+const MAX_NAME: usize = 16;
+
+pub const START_RATING: f64 = 1200.0;
+const K_FACTOR: f64 = 32.0; //most a rating moves after one game
+
+// Results kept for each player
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    pub wins: u32,
+    pub losses: u32,
+    pub best_time: Option<f64>, //fastest win in seconds
+    pub rating: f64,
+}
+
+impl Default for Stats {
+    fn default() -> Stats {
+        Stats {
+            wins: 0,
+            losses: 0,
+            best_time: None,
+            rating: START_RATING,
+        }
+    }
+}
+
+// A player's own directory under profiles/, holding their stats, settings and saved game
+pub struct Profile {
+    pub name: String,
+    pub stats: Stats,
+}
+
+impl Profile {
+    // Opens the named profile, creating it the first time the name is used
+    pub fn open(name: &str) -> io::Result<Profile> {
+        if !valid_name(name) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("invalid profile name '{}'", name)));
+        }
+        let dir = Path::new(PROFILES_DIR).join(name);
+        fs::create_dir_all(&dir)?;
+        let stats = match fs::read_to_string(dir.join(PROFILE_FILE)) {
+            Ok(text) => serde_json::from_str(&text)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Stats::default(),
+            Err(err) => return Err(err),
+        };
+        let profile = Profile {
+            name: name.to_string(),
+            stats,
+        };
+        profile.save()?;
+        Ok(profile)
+    }
+
+    pub fn dir(&self) -> PathBuf {
+        Path::new(PROFILES_DIR).join(&self.name)
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        fs::write(self.dir().join(PROFILE_FILE), serde_json::to_string_pretty(&self.stats)?)
+    }
+
+    // Counts a finished game against an opponent of the given rating, the cpu's comes
+    // from its difficulty and a networked player brings their own
+    pub fn record(&mut self, winner: Winner, time: f64, opponent: f64) -> io::Result<()> {
+        let score = if winner == Winner::Player { 1.0 } else { 0.0 };
+        self.stats.rating += K_FACTOR * (score - expected(self.stats.rating, opponent));
+        match winner {
+            Winner::Player => {
+                self.stats.wins += 1;
+                if !matches!(self.stats.best_time, Some(best) if best <= time) {
+                    self.stats.best_time = Some(time);
+                }
+            }
+            Winner::Cpu => self.stats.losses += 1,
+        }
+        self.save()
+    }
+}
+
+// Elo's chance of winning against the opponent
+fn expected(rating: f64, opponent: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((opponent - rating) / 400.0))
+}
+
+pub fn valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name.chars().count() <= MAX_NAME
+        && name.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+// Names of the profiles made so far, sorted
+pub fn list() -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(PROFILES_DIR)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().join(PROFILE_FILE).exists())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .collect();
+    names.sort();
+    names
+}
+
+// The profile select screen, with a name being typed when `name` is set
+pub struct ProfileMenu {
+    pub names: Vec<String>,
+    pub stats: Vec<Stats>,
+    pub selected: usize, //names.len() is the new profile entry
+    pub name: Option<String>,
+}
+
+impl ProfileMenu {
+    pub fn new() -> ProfileMenu {
+        ProfileMenu {
+            names: Vec::new(),
+            stats: Vec::new(),
+            selected: 0,
+            name: None,
+        }
+    }
+
+    // Reads the profiles on disk, going straight to typing a name when there are none
+    pub fn refresh(&mut self) {
+        self.names = list();
+        self.stats = self
+            .names
+            .iter()
+            .map(|name| {
+                fs::read_to_string(Path::new(PROFILES_DIR).join(name).join(PROFILE_FILE))
+                    .ok()
+                    .and_then(|text| serde_json::from_str(&text).ok())
+                    .unwrap_or_default()
+            })
+            .collect();
+        self.selected = 0;
+        self.name = self.names.is_empty().then(String::new);
+    }
+
+    pub fn up(&mut self) {
+        self.selected = (self.selected + self.names.len()) % (self.names.len() + 1);
+    }
+
+    pub fn down(&mut self) {
+        self.selected = (self.selected + 1) % (self.names.len() + 1);
+    }
+
+    pub fn type_char(&mut self, c: char) {
+        if let Some(name) = &mut self.name {
+            let longer = format!("{}{}", name, c);
+            if valid_name(&longer) {
+                *name = longer;
+            }
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use std::time::Duration;
+
+pub const LEVEL_TICKS: u64 = 1024; //the ball speeds up every this many game ticks
+
+// Where the game clock stands within its speed level. Worked out from the tick count
+// alone, so it always lies within the level and starts over at every speed-up
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LevelProgress {
+    pub level: u64, //counting from 1
+    into: u64, //ticks since the last speed-up
+}
+
+impl LevelProgress {
+    pub fn at(tick: u64) -> LevelProgress {
+        LevelProgress {
+            level: tick / LEVEL_TICKS + 1,
+            into: tick % LEVEL_TICKS,
+        }
+    }
+
+    // 0 right after a speed-up and never past 100
+    pub fn percent(&self) -> u16 {
+        (self.into * 100 / LEVEL_TICKS).min(100) as u16
+    }
+
+    pub fn ratio(&self) -> f64 {
+        self.into as f64 / LEVEL_TICKS as f64
+    }
+
+    pub fn remaining_ticks(&self) -> u64 {
+        LEVEL_TICKS - self.into
+    }
+
+    // On the game clock, `tick` to a tick
+    pub fn remaining_secs(&self, tick: Duration) -> f64 {
+        self.remaining_ticks() as f64 * tick.as_secs_f64()
+    }
+}
@@ -0,0 +1,276 @@
+use std::{
+    cell::Cell,
+    io::{self, Write},
+    rc::Rc,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use crossterm::{
+    event::{
+        DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture, KeyboardEnhancementFlags,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use ratatui::{
+    backend::{Backend, ClearType, CrosstermBackend, TestBackend, WindowSize},
+    buffer::{self, Buffer},
+    layout::{Position, Rect, Size},
+    Terminal,
+};
+
+use crate::{
+    app::App,
+    signals,
+    ui::{ui, HudCache},
+};
+
+// What the last frame cost, ratatui only sends the cells that differ from the frame before
+#[derive(Clone, Copy, Default)]
+pub struct FrameStats {
+    pub cells: usize,
+    pub bytes: usize,
+    pub hud_reused: bool,
+}
+
+// Anything that can put a frame of the game in front of someone
+pub trait Renderer {
+    fn render(&mut self, app: &App) -> io::Result<()>;
+    fn size(&self) -> io::Result<Rect>;
+    fn stats(&self) -> FrameStats {
+        FrameStats::default()
+    }
+    // The frame drawn while the app asked for a screenshot, once
+    fn screenshot(&mut self) -> Option<Buffer> {
+        None
+    }
+    // Hands the terminal back while the process is stopped, e.g. for Ctrl+Z
+    fn suspend(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// Set while the terminal has the enhanced keyboard protocol on, for leaving to undo it
+static ENHANCED_KEYS: AtomicBool = AtomicBool::new(false);
+
+// Raw mode on the alternate screen, with mouse and focus reporting, and key releases from
+// terminals that speak the enhanced keyboard protocol. Returns whether releases will come,
+// which Windows always reports
+pub fn enter_terminal() -> io::Result<bool> {
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture, EnableFocusChange)?;
+    if cfg!(windows) {
+        return Ok(true);
+    }
+    let enhanced = supports_keyboard_enhancement().unwrap_or(false);
+    if enhanced {
+        let flags = KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES | KeyboardEnhancementFlags::REPORT_EVENT_TYPES;
+        execute!(io::stdout(), PushKeyboardEnhancementFlags(flags))?;
+        ENHANCED_KEYS.store(true, Ordering::Relaxed);
+    }
+    Ok(enhanced)
+}
+
+pub fn leave_terminal() -> io::Result<()> {
+    if ENHANCED_KEYS.swap(false, Ordering::Relaxed) {
+        execute!(io::stdout(), PopKeyboardEnhancementFlags)?;
+    }
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, DisableFocusChange)
+}
+
+// Counts the bytes on their way to the terminal
+pub struct Metered<W> {
+    writer: W,
+    written: Rc<Cell<usize>>,
+}
+
+impl<W: Write> Write for Metered<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.writer.write(buf)?;
+        self.written.set(self.written.get() + n);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+// Counts the cells of each draw on their way to the backend
+pub struct Counted<B> {
+    backend: B,
+    cells: usize,
+}
+
+impl<B: Backend> Backend for Counted<B> {
+    type Error = B::Error;
+
+    fn draw<'a, I>(&mut self, content: I) -> Result<(), B::Error>
+    where
+        I: Iterator<Item = (u16, u16, &'a buffer::Cell)>,
+    {
+        let mut cells = 0;
+        self.backend.draw(content.inspect(|_| cells += 1))?;
+        self.cells = cells;
+        Ok(())
+    }
+
+    fn hide_cursor(&mut self) -> Result<(), B::Error> {
+        self.backend.hide_cursor()
+    }
+
+    fn show_cursor(&mut self) -> Result<(), B::Error> {
+        self.backend.show_cursor()
+    }
+
+    fn get_cursor_position(&mut self) -> Result<Position, B::Error> {
+        self.backend.get_cursor_position()
+    }
+
+    fn set_cursor_position<P: Into<Position>>(&mut self, position: P) -> Result<(), B::Error> {
+        self.backend.set_cursor_position(position)
+    }
+
+    fn clear(&mut self) -> Result<(), B::Error> {
+        self.backend.clear()
+    }
+
+    fn clear_region(&mut self, clear_type: ClearType) -> Result<(), B::Error> {
+        self.backend.clear_region(clear_type)
+    }
+
+    fn size(&self) -> Result<Size, B::Error> {
+        self.backend.size()
+    }
+
+    fn window_size(&mut self) -> Result<WindowSize, B::Error> {
+        self.backend.window_size()
+    }
+
+    fn flush(&mut self) -> Result<(), B::Error> {
+        self.backend.flush()
+    }
+}
+
+pub struct TerminalRenderer<W: Write> {
+    pub terminal: Terminal<Counted<CrosstermBackend<Metered<W>>>>,
+    hud: HudCache,
+    written: Rc<Cell<usize>>,
+    stats: FrameStats,
+    shot: Option<Buffer>,
+}
+
+impl<W: Write> TerminalRenderer<W> {
+    pub fn new(writer: W) -> io::Result<TerminalRenderer<W>> {
+        let written = Rc::new(Cell::new(0));
+        let writer = Metered {
+            writer,
+            written: written.clone(),
+        };
+        let backend = Counted {
+            backend: CrosstermBackend::new(writer),
+            cells: 0,
+        };
+        Ok(TerminalRenderer {
+            terminal: Terminal::new(backend)?,
+            hud: HudCache::default(),
+            written,
+            stats: FrameStats::default(),
+            shot: None,
+        })
+    }
+}
+
+impl<W: Write> Renderer for TerminalRenderer<W> {
+    fn render(&mut self, app: &App) -> io::Result<()> {
+        let hud = &mut self.hud;
+        let before = self.written.get();
+        let frame = self.terminal.draw(|f| ui(f, app, hud))?;
+        if app.screenshot {
+            self.shot = Some(frame.buffer.clone());
+        }
+        self.stats = FrameStats {
+            cells: self.terminal.backend().cells,
+            bytes: self.written.get() - before,
+            hud_reused: self.hud.reused,
+        };
+        Ok(())
+    }
+
+    fn size(&self) -> io::Result<Rect> {
+        self.terminal.size().map(Rect::from)
+    }
+
+    fn stats(&self) -> FrameStats {
+        self.stats
+    }
+
+    fn screenshot(&mut self) -> Option<Buffer> {
+        self.shot.take()
+    }
+
+    fn suspend(&mut self) -> io::Result<()> {
+        leave_terminal()?;
+        self.terminal.show_cursor()?;
+        signals::stop();
+        enter_terminal()?;
+        self.terminal.clear() //whatever the shell drew is gone after the next frame
+    }
+}
+
+// Renders into memory and keeps every frame as plain text, one line per row
+pub struct TestRenderer {
+    terminal: Terminal<TestBackend>,
+    hud: HudCache,
+    pub frames: Vec<String>,
+}
+
+impl TestRenderer {
+    pub fn new(width: u16, height: u16) -> TestRenderer {
+        TestRenderer {
+            terminal: Terminal::new(TestBackend::new(width, height)).unwrap(),
+            hud: HudCache::default(),
+            frames: Vec::new(),
+        }
+    }
+
+    pub fn buffer(&self) -> &Buffer {
+        self.terminal.backend().buffer()
+    }
+
+    pub fn last_frame(&self) -> Option<&str> {
+        self.frames.last().map(String::as_str)
+    }
+}
+
+impl Renderer for TestRenderer {
+    fn render(&mut self, app: &App) -> io::Result<()> {
+        let hud = &mut self.hud;
+        let Ok(_) = self.terminal.draw(|f| ui(f, app, hud)); //drawing into memory can't fail
+        self.frames.push(buffer_text(self.buffer()));
+        Ok(())
+    }
+
+    fn size(&self) -> io::Result<Rect> {
+        let Ok(size) = self.terminal.size();
+        Ok(size.into())
+    }
+
+    fn screenshot(&mut self) -> Option<Buffer> {
+        Some(self.buffer().clone())
+    }
+}
+
+pub fn buffer_text(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    let mut text = String::new();
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            text.push_str(buffer[(x, y)].symbol());
+        }
+        text.push('\n');
+    }
+    text
+}
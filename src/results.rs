@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+pub const CELEBRATION_TICKS: u64 = 120; //how long a win is celebrated before the results come up
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Choice {
+    NextGame,
+    KeepPlaying,
+    Rematch, //the same game again with the ends swapped
+    Restart,
+    Menu,
+    Quit,
+}
+
+impl Choice {
+    // Locale key of the label
+    pub fn key(self) -> &'static str {
+        match self {
+            Choice::NextGame => "results.next_game",
+            Choice::KeepPlaying => "results.keep_playing",
+            Choice::Rematch => "results.rematch",
+            Choice::Restart => "results.restart",
+            Choice::Menu => "results.menu",
+            Choice::Quit => "results.quit",
+        }
+    }
+}
+
+// What can be done once a match is over, after the celebration of a win. The way on
+// comes first
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Results {
+    pub selected: usize,
+    choices: Vec<Choice>,
+}
+
+impl Results {
+    pub fn new(next_game: bool, keep_playing: bool) -> Results {
+        let mut choices = Vec::new();
+        if next_game {
+            choices.push(Choice::NextGame);
+        }
+        if keep_playing {
+            choices.push(Choice::KeepPlaying);
+        }
+        choices.extend([Choice::Rematch, Choice::Restart, Choice::Menu, Choice::Quit]);
+        Results { selected: 0, choices }
+    }
+
+    pub fn choices(&self) -> &[Choice] {
+        &self.choices
+    }
+
+    pub fn up(&mut self) {
+        self.selected = (self.selected + self.choices.len() - 1) % self.choices.len();
+    }
+
+    pub fn down(&mut self) {
+        self.selected = (self.selected + 1) % self.choices.len();
+    }
+
+    pub fn choice(&self) -> Choice {
+        self.choices[self.selected]
+    }
+}
@@ -0,0 +1,352 @@
+use std::{fs, io};
+
+use rand_chacha::ChaCha12Rng;
+use serde::{Deserialize, Serialize};
+use ratatui::{style::Color, widgets::canvas::Rectangle};
+
+use crate::{
+    app::{App, Orientation, RandomSignal},
+    boss::{self, Boss},
+    combo::ComboMeter,
+    court::Court,
+    level::Level,
+    modifiers::{Modifiers, Visibility},
+    results::Results,
+    series::Series,
+    splits::{self, Splits},
+    targets::Targets,
+    world::{Entity, Kind, Velocity, World},
+};
+
+pub const SAVE_FILE: &str = "pong_terminal.save";
+
+#[derive(Serialize, Deserialize)]
+struct Body {
+    x: f64,
+    y: f64,
+    width: f64,
+    height: f64,
+}
+
+impl Body {
+    fn of(rect: &Rectangle) -> Body {
+        Body {
+            x: rect.x,
+            y: rect.y,
+            width: rect.width,
+            height: rect.height,
+        }
+    }
+
+    fn rect(&self) -> Rectangle {
+        Rectangle {
+            x: self.x,
+            y: self.y,
+            width: self.width,
+            height: self.height,
+            color: Color::Reset,
+        }
+    }
+
+    fn apply(&self, rect: &mut Rectangle) {
+        rect.x = self.x;
+        rect.y = self.y;
+        rect.width = self.width;
+        rect.height = self.height;
+    }
+}
+
+// Bricks are kept in the editor's text layout, their rectangles follow from the grid
+#[derive(Serialize, Deserialize)]
+struct SavedLevel {
+    index: u32,
+    layout: String,
+}
+
+impl SavedLevel {
+    fn of(level: &Level) -> SavedLevel {
+        SavedLevel {
+            index: level.index,
+            layout: level.to_text(),
+        }
+    }
+
+    fn restore(&self) -> io::Result<Level> {
+        let mut level = Level::parse(&self.layout)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        level.index = self.index;
+        Ok(level)
+    }
+}
+
+// One of the world's entities that come and go, the ball and paddles have fields of their own
+#[derive(Serialize, Deserialize)]
+struct SavedEntity {
+    kind: Kind,
+    body: Body,
+    velocity: Option<Velocity>,
+    expires: Option<u64>,
+}
+
+impl SavedEntity {
+    fn of(world: &World, entity: Entity, kind: Kind) -> SavedEntity {
+        SavedEntity {
+            kind,
+            body: Body::of(&world[entity]),
+            velocity: world.velocity(entity),
+            expires: world.expiry(entity),
+        }
+    }
+
+    fn restore(&self, world: &mut World) {
+        let entity = world.spawn(self.kind, self.body.rect(), self.velocity);
+        if let Some(tick) = self.expires {
+            world.expire_at(entity, tick);
+        }
+    }
+}
+
+// What the game was started with on the command line, so a run resumed from another one
+// still plays by its own rules and keeps to its own seed's leaderboard
+#[derive(Serialize, Deserialize)]
+struct Rules {
+    seed: u64,
+    win_score: u16,
+    rally: bool,
+    hardcore: bool,
+    practice: bool,
+    shrink: bool,
+    vertical: bool,
+    orientation: Orientation,
+    speed: f64,
+    max_speed: f64,
+    slow_motion: f64,
+}
+
+impl Rules {
+    fn of(app: &App) -> Rules {
+        Rules {
+            seed: app.history.seed,
+            win_score: app.win_score,
+            rally: app.rally,
+            hardcore: app.hardcore,
+            practice: app.practice,
+            shrink: app.shrink,
+            vertical: app.vertical,
+            orientation: app.orientation,
+            speed: app.speed,
+            max_speed: app.max_speed,
+            slow_motion: app.slow_motion,
+        }
+    }
+
+    fn apply(&self, app: &mut App) {
+        app.history.seed = self.seed;
+        app.win_score = self.win_score;
+        app.rally = self.rally;
+        app.hardcore = self.hardcore;
+        app.practice = self.practice;
+        app.shrink = self.shrink;
+        app.vertical = self.vertical;
+        app.orientation = self.orientation;
+        app.speed = self.speed;
+        app.max_speed = self.max_speed;
+        app.slow_motion = self.slow_motion;
+    }
+}
+
+// Everything that moves the game forward, so a resumed session carries on exactly where it stopped
+#[derive(Serialize, Deserialize)]
+pub struct SaveState {
+    #[serde(default)]
+    rules: Option<Rules>, //older saves play by the command line's
+    ball: Body,
+    board: Body,
+    cpu: Body,
+    vx: f64,
+    vy: f64,
+    rx: f64,
+    dir_x: bool,
+    dir_y: bool,
+    score: u16,
+    cpu_score: u16,
+    combo: u16,
+    #[serde(default)]
+    meter: ComboMeter,
+    series: Option<Series>,
+    tick_count: u64,
+    rng: ChaCha12Rng,
+    signal: RandomSignal,
+    streamdata: Vec<u64>,
+    procedural: bool,
+    level: Option<SavedLevel>,
+    custom_level: Option<SavedLevel>,
+    win: bool,
+    #[serde(default)]
+    endless: bool,
+    #[serde(default)]
+    flipped: bool,
+    lost: bool,
+    win_time: f64,
+    #[serde(default)]
+    modifiers: Modifiers,
+    #[serde(default)]
+    walls: u16, //how far each wall has closed in
+    #[serde(default)]
+    boss: Option<u16>, //the health left of the boss on a boss stage
+    #[serde(default)]
+    entities: Vec<SavedEntity>, //the boss and its extra balls, and the rings of target practice
+    #[serde(default)]
+    targets: Option<u64>, //target practice only, the tick its next ring comes up at
+    #[serde(default)]
+    splits: Option<Vec<f64>>, //time attack only, the best run is read back from its own file
+    #[serde(default)]
+    celebration: u64,
+    #[serde(default)]
+    results: Option<Results>,
+    #[serde(default)]
+    slow_ticks: u16,
+    #[serde(default = "Visibility::new")]
+    visibility: Visibility,
+    #[serde(default)]
+    idle_ticks: u64,
+    #[serde(default)]
+    idle: bool,
+}
+
+impl SaveState {
+    pub fn capture(app: &App) -> SaveState {
+        let ((vx, vy), (dir_x, dir_y)) = (app.ball_speed(), app.heading());
+        SaveState {
+            rules: Some(Rules::of(app)),
+            ball: Body::of(&app.world[app.ball]),
+            board: Body::of(&app.world[app.board]),
+            cpu: Body::of(&app.world[app.cpu]),
+            vx,
+            vy,
+            rx: app.rx,
+            dir_x,
+            dir_y,
+            score: app.score,
+            cpu_score: app.cpu_score,
+            combo: app.combo,
+            meter: app.meter,
+            series: app.series,
+            tick_count: app.tick_count,
+            rng: app.rng.clone(),
+            signal: app.signal.clone(),
+            streamdata: app.streamdata.clone(),
+            procedural: app.procedural,
+            level: app.level.as_ref().map(|level| SavedLevel::of(&level.standing(&app.world))),
+            custom_level: app.custom_level.as_ref().map(SavedLevel::of),
+            win: app.win,
+            endless: app.endless,
+            flipped: app.flipped,
+            lost: app.lost,
+            win_time: app.win_time,
+            modifiers: app.modifiers,
+            walls: app.playground.walls(),
+            boss: app.boss.as_ref().map(|boss| boss.health),
+            entities: app
+                .world
+                .entities()
+                .filter(|(_, kind)| kind.passing())
+                .map(|(entity, kind)| SavedEntity::of(&app.world, entity, kind))
+                .collect(),
+            targets: app.targets.as_ref().map(|targets| targets.next_spawn),
+            splits: app.splits.as_ref().map(|splits| splits.times.clone()),
+            celebration: app.celebration,
+            results: app.results.clone(),
+            slow_ticks: app.slow_ticks,
+            visibility: app.visibility,
+            idle_ticks: app.idle_ticks,
+            idle: app.idle,
+        }
+    }
+
+    pub fn restore(self, app: &mut App) -> io::Result<()> {
+        let level = self.level.as_ref().map(SavedLevel::restore).transpose()?;
+        let custom_level = self.custom_level.as_ref().map(SavedLevel::restore).transpose()?;
+
+        if let Some(rules) = &self.rules {
+            rules.apply(app);
+        }
+        self.ball.apply(&mut app.world[app.ball]);
+        self.board.apply(&mut app.world[app.board]);
+        self.cpu.apply(&mut app.world[app.cpu]);
+        app.rx = self.rx;
+        let velocity = Velocity { x: self.vx + self.rx, y: self.vy }.turned(Some(self.dir_x), Some(self.dir_y));
+        app.world.set_velocity(app.ball, velocity);
+        app.score = self.score;
+        app.cpu_score = self.cpu_score;
+        app.combo = self.combo;
+        app.meter = self.meter;
+        app.series = self.series;
+        app.tick_count = self.tick_count;
+        app.rng = self.rng;
+        app.signal = self.signal;
+        app.streamdata = self.streamdata;
+        app.procedural = self.procedural;
+        app.set_level(level);
+        app.custom_level = custom_level;
+        app.win = self.win;
+        app.endless = self.endless;
+        app.flipped = self.flipped;
+        app.lost = self.lost;
+        app.win_time = self.win_time;
+        app.modifiers = self.modifiers;
+        app.playground = Court::FULL.inset(self.walls);
+        app.boss = match (self.boss, &app.level) {
+            (Some(health), Some(level)) => {
+                let mut boss = Boss::new(level.index);
+                boss.health = health;
+                Some(boss)
+            }
+            _ => None,
+        };
+        app.targets = self.targets.map(|next_spawn| Targets { next_spawn });
+        app.splits = self.splits.map(|times| Splits {
+            times,
+            ..Splits::load(&app.file(&splits::file_name(app.win_score)))
+        });
+        app.celebration = self.celebration;
+        app.results = self.results;
+        app.slow_ticks = self.slow_ticks;
+        app.visibility = self.visibility;
+        app.idle_ticks = self.idle_ticks;
+        app.idle = self.idle;
+        app.world.clear();
+        for entity in self.entities.iter().filter(|entity| entity.kind.passing()) {
+            entity.restore(&mut app.world);
+        }
+        if app.boss.is_some() && app.world.count(Kind::Boss) == 0 {
+            let (body, velocity) = boss::body(app.playground); //saved before the boss was one of the world's
+            app.world.spawn(Kind::Boss, body, Some(velocity));
+        }
+        app.cast_shadow();
+        Ok(())
+    }
+}
+
+// The save lives with the current profile, or in the working directory without one
+pub fn exists(app: &App) -> bool {
+    app.file(SAVE_FILE).exists()
+}
+
+pub fn save(app: &App) -> io::Result<()> {
+    fs::write(app.file(SAVE_FILE), to_json(app)?)
+}
+
+pub fn load(app: &mut App) -> io::Result<()> {
+    from_json(app, &fs::read_to_string(app.file(SAVE_FILE))?)
+}
+
+// The save as it goes in the file
+pub fn to_json(app: &App) -> io::Result<String> {
+    Ok(serde_json::to_string(&SaveState::capture(app))?)
+}
+
+pub fn from_json(app: &mut App, json: &str) -> io::Result<()> {
+    let state: SaveState = serde_json::from_str(json)?;
+    state.restore(app)
+}
@@ -0,0 +1,240 @@
+use crossterm::event::KeyCode;
+use log::info;
+use ratatui::Frame;
+
+use crate::{
+    app::{App, Mode},
+    input,
+    ui::{self, HudCache},
+};
+
+// How the clock drives a screen: the game's ticks, paced and held while the terminal is
+// too small, an animation that only needs to keep moving, or not at all
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Clock {
+    Still,
+    Animation,
+    Game,
+}
+
+// One screen of the game, what its keys do, what a tick of the clock does while it is in
+// front and how it is drawn. Keys that work everywhere, like help and screenshots, are
+// taken before the screen sees them
+pub trait Screen {
+    // Returns true when the app should quit
+    fn handle_input(&self, app: &mut App, code: KeyCode) -> bool;
+    fn update(&self, _app: &mut App) {}
+    fn render(&self, f: &mut Frame, app: &App, hud: &mut HudCache);
+    fn clock(&self, _app: &App) -> Clock {
+        Clock::Still
+    }
+    // The lines of keys the help overlay starts with, the court's unless the screen has its own
+    fn help(&self, app: &App) -> Vec<String> {
+        ui::court_help(app)
+    }
+}
+
+struct Menu;
+struct Demo;
+struct Play;
+struct Paused;
+struct Editor;
+struct Settings;
+struct Profiles;
+struct InstantReplay;
+
+impl Mode {
+    pub fn screen(self) -> &'static dyn Screen {
+        match self {
+            Mode::Menu => &Menu,
+            Mode::Demo => &Demo,
+            Mode::Play => &Play,
+            Mode::Paused => &Paused,
+            Mode::Edit => &Editor,
+            Mode::Settings => &Settings,
+            Mode::Profiles => &Profiles,
+            Mode::InstantReplay => &InstantReplay,
+        }
+    }
+}
+
+impl Screen for Menu {
+    fn handle_input(&self, app: &mut App, code: KeyCode) -> bool {
+        input::menu_key(app, code)
+    }
+
+    fn update(&self, app: &mut App) {
+        app.menu.frame += 1; //the menu only animates, the game stays put
+    }
+
+    fn render(&self, f: &mut Frame, app: &App, _hud: &mut HudCache) {
+        ui::menu_ui(f, app);
+    }
+
+    fn clock(&self, app: &App) -> Clock {
+        if app.reduced_motion {
+            Clock::Still
+        } else {
+            Clock::Animation
+        }
+    }
+
+    fn help(&self, app: &App) -> Vec<String> {
+        vec![app.locale.text("help.menu")]
+    }
+}
+
+// Any key ends the demo
+impl Screen for Demo {
+    fn handle_input(&self, app: &mut App, _code: KeyCode) -> bool {
+        input::end_demo(app);
+        false
+    }
+
+    fn update(&self, app: &mut App) {
+        app.on_tick();
+    }
+
+    fn render(&self, f: &mut Frame, app: &App, hud: &mut HudCache) {
+        ui::game_ui(f, app, hud);
+    }
+
+    fn clock(&self, _app: &App) -> Clock {
+        Clock::Game
+    }
+}
+
+impl Screen for Play {
+    fn handle_input(&self, app: &mut App, code: KeyCode) -> bool {
+        if app.results.is_some() {
+            input::results_key(app, code)
+        } else {
+            input::play_key(app, code)
+        }
+    }
+
+    fn update(&self, app: &mut App) {
+        app.on_tick();
+    }
+
+    fn render(&self, f: &mut Frame, app: &App, hud: &mut HudCache) {
+        ui::game_ui(f, app, hud);
+        if let Some(results) = &app.results {
+            ui::results_ui(f, app, results);
+        }
+    }
+
+    fn clock(&self, _app: &App) -> Clock {
+        Clock::Game
+    }
+}
+
+impl Screen for Paused {
+    fn handle_input(&self, app: &mut App, code: KeyCode) -> bool {
+        input::paused_key(app, code)
+    }
+
+    fn render(&self, f: &mut Frame, app: &App, hud: &mut HudCache) {
+        ui::game_ui(f, app, hud);
+        ui::paused_ui(f, app);
+    }
+}
+
+impl Screen for Editor {
+    fn handle_input(&self, app: &mut App, code: KeyCode) -> bool {
+        input::editor_key(app, code)
+    }
+
+    fn render(&self, f: &mut Frame, app: &App, _hud: &mut HudCache) {
+        ui::editor_ui(f, app);
+    }
+
+    fn help(&self, app: &App) -> Vec<String> {
+        vec![app.locale.text("editor.keys")]
+    }
+}
+
+impl Screen for Settings {
+    fn handle_input(&self, app: &mut App, code: KeyCode) -> bool {
+        input::settings_key(app, code);
+        false
+    }
+
+    fn render(&self, f: &mut Frame, app: &App, hud: &mut HudCache) {
+        ui::game_ui(f, app, hud); //the court behind is the preview
+        ui::settings_ui(f, app);
+    }
+
+    fn help(&self, app: &App) -> Vec<String> {
+        vec![app.locale.text("settings.keys")]
+    }
+}
+
+impl Screen for Profiles {
+    fn handle_input(&self, app: &mut App, code: KeyCode) -> bool {
+        input::profiles_key(app, code)
+    }
+
+    fn render(&self, f: &mut Frame, app: &App, _hud: &mut HudCache) {
+        ui::profiles_ui(f, app);
+    }
+}
+
+// q quits the way it does from the live game, any other key goes back to it
+impl Screen for InstantReplay {
+    fn handle_input(&self, app: &mut App, code: KeyCode) -> bool {
+        app.stop_instant_replay();
+        code == KeyCode::Char('q') && input::quit(app)
+    }
+
+    fn update(&self, app: &mut App) {
+        app.instant_replay_tick();
+    }
+
+    fn render(&self, f: &mut Frame, app: &App, hud: &mut HudCache) {
+        ui::game_ui(f, app, hud);
+    }
+
+    fn clock(&self, _app: &App) -> Clock {
+        Clock::Game
+    }
+}
+
+// The screens open, in the order they were opened. The last one is in front, and going
+// back from one shows the screen it was opened over. Never empty
+pub struct Navigator {
+    stack: Vec<Mode>,
+}
+
+impl Navigator {
+    pub fn new(mode: Mode) -> Navigator {
+        Navigator { stack: vec![mode] }
+    }
+
+    pub fn current(&self) -> Mode {
+        *self.stack.last().unwrap()
+    }
+
+    // Leaves for another screen, forgetting the way there
+    pub fn replace(&mut self, mode: Mode) {
+        if mode != self.current() {
+            info!("mode {:?} -> {:?}", self.current(), mode);
+        }
+        self.stack.clear();
+        self.stack.push(mode);
+    }
+
+    // Opens a screen over the current one, for `back` to return to
+    pub fn push(&mut self, mode: Mode) {
+        info!("mode {:?} -> {:?}", self.current(), mode);
+        self.stack.push(mode);
+    }
+
+    // Closes the screen in front, the bottom one stays open
+    pub fn back(&mut self) {
+        if self.stack.len() > 1 {
+            let closed = self.stack.pop().unwrap();
+            info!("mode {:?} -> {:?}", closed, self.current());
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use std::{
+    fmt::Write as _,
+    fs, io,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    style::{Color, Modifier},
+};
+
+// Named after the time it was taken, so one never overwrites another
+pub fn file_name() -> String {
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_millis()).unwrap_or(0);
+    format!("pong_terminal-screenshot-{}.txt", millis)
+}
+
+pub fn save(buffer: &Buffer, path: &Path) -> io::Result<()> {
+    fs::write(path, ansi(buffer))
+}
+
+// The frame as UTF-8 text with ANSI colours, `cat` shows it as it was on screen. Styles
+// are only written where they change and reset at the end of every row
+pub fn ansi(buffer: &Buffer) -> String {
+    let area = buffer.area;
+    let mut text = String::new();
+    for y in area.top()..area.bottom() {
+        let mut style = None;
+        for x in area.left()..area.right() {
+            let cell = &buffer[(x, y)];
+            let cell_style = (cell.fg, cell.bg, cell.modifier);
+            if style != Some(cell_style) {
+                text.push_str("\x1b[0");
+                for (flag, code) in MODIFIERS {
+                    if cell.modifier.contains(flag) {
+                        let _ = write!(text, ";{}", code);
+                    }
+                }
+                colour(&mut text, cell.fg, 30);
+                colour(&mut text, cell.bg, 40);
+                text.push('m');
+                style = Some(cell_style);
+            }
+            text.push_str(cell.symbol());
+        }
+        text.push_str("\x1b[0m\n");
+    }
+    text
+}
+
+const MODIFIERS: [(Modifier, u8); 9] = [
+    (Modifier::BOLD, 1),
+    (Modifier::DIM, 2),
+    (Modifier::ITALIC, 3),
+    (Modifier::UNDERLINED, 4),
+    (Modifier::SLOW_BLINK, 5),
+    (Modifier::RAPID_BLINK, 6),
+    (Modifier::REVERSED, 7),
+    (Modifier::HIDDEN, 8),
+    (Modifier::CROSSED_OUT, 9),
+];
+
+// Appends the SGR parameters for a colour, `base` is 30 for the foreground and 40 for the
+// background
+fn colour(text: &mut String, color: Color, base: u8) {
+    let _ = match color {
+        Color::Reset => Ok(()),
+        Color::Black => write!(text, ";{}", base),
+        Color::Red => write!(text, ";{}", base + 1),
+        Color::Green => write!(text, ";{}", base + 2),
+        Color::Yellow => write!(text, ";{}", base + 3),
+        Color::Blue => write!(text, ";{}", base + 4),
+        Color::Magenta => write!(text, ";{}", base + 5),
+        Color::Cyan => write!(text, ";{}", base + 6),
+        Color::Gray => write!(text, ";{}", base + 7),
+        Color::DarkGray => write!(text, ";{}", base + 60),
+        Color::LightRed => write!(text, ";{}", base + 61),
+        Color::LightGreen => write!(text, ";{}", base + 62),
+        Color::LightYellow => write!(text, ";{}", base + 63),
+        Color::LightBlue => write!(text, ";{}", base + 64),
+        Color::LightMagenta => write!(text, ";{}", base + 65),
+        Color::LightCyan => write!(text, ";{}", base + 66),
+        Color::White => write!(text, ";{}", base + 67),
+        Color::Indexed(i) => write!(text, ";{};5;{}", base + 8, i),
+        Color::Rgb(r, g, b) => write!(text, ";{};2;{};{};{}", base + 8, r, g, b),
+    };
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Winner {
+    Player,
+    Cpu,
+}
+
+// Games won by each side in a best-of-N match
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct Series {
+    pub best_of: u16,
+    pub player: u16,
+    pub cpu: u16,
+}
+
+impl Series {
+    pub fn new(best_of: u16) -> Series {
+        Series {
+            best_of,
+            player: 0,
+            cpu: 0,
+        }
+    }
+
+    // Games needed to take the series, a majority of best_of
+    pub fn needed(&self) -> u16 {
+        self.best_of / 2 + 1
+    }
+
+    // The game currently being played, counting from 1
+    pub fn game(&self) -> u16 {
+        self.player + self.cpu + 1
+    }
+
+    pub fn record(&mut self, winner: Winner) {
+        match winner {
+            Winner::Player => self.player += 1,
+            Winner::Cpu => self.cpu += 1,
+        }
+    }
+
+    pub fn winner(&self) -> Option<Winner> {
+        if self.player >= self.needed() {
+            Some(Winner::Player)
+        } else if self.cpu >= self.needed() {
+            Some(Winner::Cpu)
+        } else {
+            None
+        }
+    }
+}
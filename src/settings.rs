@@ -0,0 +1,251 @@
+use std::{fs, io, ops::RangeInclusive, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{modifiers::Modifiers, palette::PALETTES};
+
+pub const SETTINGS_FILE: &str = "pong_terminal.settings";
+
+// Keys the game already uses, a binding can't take them over
+const RESERVED: &str = "qrpdsheio?[]-=,.";
+
+// Court units the player's paddle moves a step, for both the settings and the command line
+pub const PADDLE_SPEEDS: RangeInclusive<u8> = 1..=10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    // How fast the cpu paddle moves compared to normal
+    pub fn speed(self) -> f64 {
+        match self {
+            Difficulty::Easy => 0.6,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.4,
+        }
+    }
+
+    // The cpu's rating at this difficulty, for a player's own rating to move against
+    pub fn rating(self) -> f64 {
+        match self {
+            Difficulty::Easy => 1000.0,
+            Difficulty::Normal => 1200.0,
+            Difficulty::Hard => 1400.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MarkerStyle {
+    Braille,
+    Block,
+    Dot,
+}
+
+// How the player's paddle gets up to speed while an arrow is held and comes to rest once
+// it is let go. Off moves it at full speed from the first tick and stops it dead
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Acceleration {
+    Off,
+    Smooth,
+    Heavy,
+}
+
+impl Acceleration {
+    // Ticks from rest to full speed, and back
+    pub fn ticks(self) -> u8 {
+        match self {
+            Acceleration::Off => 1,
+            Acceleration::Smooth => 4,
+            Acceleration::Heavy => 10,
+        }
+    }
+}
+
+// Drawn behind the court, off unless picked since it costs a layer every frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Background {
+    Off,
+    Stars,
+    Grid,
+}
+
+// What the settings screen changes, kept between runs in SETTINGS_FILE next to the
+// profile. Options given on the command line win over the file
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub palette: String,
+    pub volume: u8, //percent
+    pub difficulty: Difficulty,
+    pub paddle_speed: u8, //court units a step of the arrows
+    pub acceleration: Acceleration,
+    pub marker: MarkerStyle,
+    pub half_blocks: bool, //paddles and ball drawn to half a cell
+    pub background: Background,
+    pub left: Option<char>, //extra keys next to the arrows
+    pub right: Option<char>,
+    pub modifiers: Modifiers, //for the next match, one under way keeps its own
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings {
+            palette: String::from(PALETTES[0]),
+            volume: 100,
+            difficulty: Difficulty::Normal,
+            paddle_speed: 5,
+            acceleration: Acceleration::Off,
+            marker: MarkerStyle::Braille,
+            half_blocks: false,
+            background: Background::Off,
+            left: None,
+            right: None,
+            modifiers: Modifiers::default(),
+        }
+    }
+}
+
+impl Settings {
+    // A missing or unreadable file just means the defaults
+    pub fn load(path: &Path) -> Settings {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Row {
+    Palette,
+    Volume,
+    Difficulty,
+    Marker,
+    HalfBlocks,
+    Background,
+    PaddleSpeed,
+    Acceleration,
+    LeftKey,
+    RightKey,
+    Mirror,
+    Blink,
+    Walls,
+    Shadow,
+}
+
+pub const ROWS: [Row; 14] = [
+    Row::Palette,
+    Row::Volume,
+    Row::Difficulty,
+    Row::Marker,
+    Row::HalfBlocks,
+    Row::Background,
+    Row::PaddleSpeed,
+    Row::Acceleration,
+    Row::LeftKey,
+    Row::RightKey,
+    Row::Mirror,
+    Row::Blink,
+    Row::Walls,
+    Row::Shadow,
+];
+
+// Cursor of the settings screen, `binding` is set while waiting for the key to bind
+pub struct SettingsMenu {
+    pub selected: usize,
+    pub binding: bool,
+}
+
+impl SettingsMenu {
+    pub fn new() -> SettingsMenu {
+        SettingsMenu {
+            selected: 0,
+            binding: false,
+        }
+    }
+
+    pub fn row(&self) -> Row {
+        ROWS[self.selected]
+    }
+
+    pub fn up(&mut self) {
+        self.selected = (self.selected + ROWS.len() - 1) % ROWS.len();
+    }
+
+    pub fn down(&mut self) {
+        self.selected = (self.selected + 1) % ROWS.len();
+    }
+}
+
+// Steps the selected row one way or the other, returns false for the key rows which
+// are changed by binding instead
+pub fn step(settings: &mut Settings, row: Row, forward: bool) -> bool {
+    fn next<T: Copy + PartialEq>(values: &[T], current: T, forward: bool) -> T {
+        let i = values.iter().position(|v| *v == current).unwrap_or(0);
+        let len = values.len();
+        values[if forward { (i + 1) % len } else { (i + len - 1) % len }]
+    }
+
+    match row {
+        Row::Palette => {
+            let name = next(&PALETTES, settings.palette.as_str(), forward);
+            settings.palette = String::from(name);
+        }
+        Row::Volume => {
+            settings.volume = if forward {
+                (settings.volume + 10).min(100)
+            } else {
+                settings.volume.saturating_sub(10)
+            };
+        }
+        Row::Difficulty => {
+            let levels = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+            settings.difficulty = next(&levels, settings.difficulty, forward);
+        }
+        Row::Marker => {
+            let styles = [MarkerStyle::Braille, MarkerStyle::Block, MarkerStyle::Dot];
+            settings.marker = next(&styles, settings.marker, forward);
+        }
+        Row::HalfBlocks => settings.half_blocks = !settings.half_blocks,
+        Row::Background => {
+            let backgrounds = [Background::Off, Background::Stars, Background::Grid];
+            settings.background = next(&backgrounds, settings.background, forward);
+        }
+        Row::PaddleSpeed => {
+            let speed = if forward { settings.paddle_speed.saturating_add(1) } else { settings.paddle_speed.saturating_sub(1) };
+            settings.paddle_speed = speed.clamp(*PADDLE_SPEEDS.start(), *PADDLE_SPEEDS.end());
+        }
+        Row::Acceleration => {
+            let curves = [Acceleration::Off, Acceleration::Smooth, Acceleration::Heavy];
+            settings.acceleration = next(&curves, settings.acceleration, forward);
+        }
+        Row::Mirror => settings.modifiers.mirror = !settings.modifiers.mirror,
+        Row::Blink => settings.modifiers.blink = !settings.modifiers.blink,
+        Row::Walls => settings.modifiers.walls = !settings.modifiers.walls,
+        Row::Shadow => settings.modifiers.shadow = !settings.modifiers.shadow,
+        Row::LeftKey | Row::RightKey => return false,
+    }
+    true
+}
+
+// Binds a key to the selected row, reserved keys are refused
+pub fn bind(settings: &mut Settings, row: Row, key: char) -> bool {
+    if RESERVED.contains(key) || key.is_whitespace() {
+        return false;
+    }
+    match row {
+        Row::LeftKey => settings.left = Some(key),
+        Row::RightKey => settings.right = Some(key),
+        _ => return false,
+    }
+    true
+}
@@ -0,0 +1,82 @@
+use futures::{channel::mpsc, future, StreamExt};
+use log::warn;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Signal {
+    Suspend,
+    Interrupt,
+    Terminate,
+}
+
+impl Signal {
+    // What the shell expects from a process that ended on the signal
+    pub fn exit_code(self) -> i32 {
+        match self {
+            Signal::Suspend => 0,
+            Signal::Interrupt => 130,
+            Signal::Terminate => 143,
+        }
+    }
+}
+
+// Unix signals the game reacts to, forwarded from a watcher thread so the main loop can
+// select on them. Elsewhere, or if the watcher can't start, nothing ever arrives
+pub struct Signals {
+    rx: Option<mpsc::UnboundedReceiver<Signal>>,
+}
+
+impl Signals {
+    pub fn listen() -> Signals {
+        #[cfg(unix)]
+        let rx = match watch() {
+            Ok(rx) => Some(rx),
+            Err(err) => {
+                warn!("can't watch for signals: {}", err);
+                None
+            }
+        };
+        #[cfg(not(unix))]
+        let rx = None;
+        Signals { rx }
+    }
+
+    pub async fn next(&mut self) -> Signal {
+        match &mut self.rx {
+            Some(rx) => match rx.next().await {
+                Some(signal) => signal,
+                None => future::pending().await,
+            },
+            None => future::pending().await,
+        }
+    }
+}
+
+#[cfg(unix)]
+fn watch() -> std::io::Result<mpsc::UnboundedReceiver<Signal>> {
+    use signal_hook::consts::{SIGINT, SIGTERM, SIGTSTP};
+
+    let mut signals = signal_hook::iterator::Signals::new([SIGTSTP, SIGINT, SIGTERM])?;
+    let (tx, rx) = mpsc::unbounded();
+    std::thread::spawn(move || {
+        for signal in signals.forever() {
+            let signal = match signal {
+                SIGTSTP => Signal::Suspend,
+                SIGINT => Signal::Interrupt,
+                SIGTERM => Signal::Terminate,
+                _ => continue,
+            };
+            if tx.unbounded_send(signal).is_err() {
+                break;
+            }
+        }
+    });
+    Ok(rx)
+}
+
+// Stops the process the way SIGTSTP normally would, returning once the shell continues it
+pub fn stop() {
+    #[cfg(unix)]
+    if let Err(err) = signal_hook::low_level::emulate_default_handler(signal_hook::consts::SIGTSTP) {
+        warn!("can't suspend: {}", err);
+    }
+}
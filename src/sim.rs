@@ -0,0 +1,309 @@
+use std::io;
+
+use crossterm::event::KeyCode;
+use serde::Serialize;
+
+use crate::{
+    app::{serve, App, Mode},
+    config::Config,
+    court::Court,
+    events::{GameEvent, Paddle},
+    history::{Input, InputSource},
+    input::apply_input,
+    render::Renderer,
+    save,
+    screen::Clock,
+    series::Winner,
+    settings::Difficulty,
+};
+
+const MAX_TICKS: u64 = 100_000; //a game still going after this long is left unfinished
+
+// Headless, silent access to the game simulation for benchmarks and tests
+pub struct Simulation {
+    app: App,
+}
+
+impl Simulation {
+    pub fn new(seed: u64) -> Simulation {
+        Simulation::with_config(Config {
+            seed: Some(seed),
+            ..Config::default()
+        })
+    }
+
+    pub fn procedural(seed: u64) -> Simulation {
+        Simulation::with_config(Config {
+            seed: Some(seed),
+            procedural: true,
+            ..Config::default()
+        })
+    }
+
+    pub fn rally(seed: u64) -> Simulation {
+        Simulation::with_config(Config {
+            seed: Some(seed),
+            rally: true,
+            ..Config::default()
+        })
+    }
+
+    pub fn targets(seed: u64) -> Simulation {
+        Simulation::with_config(Config {
+            seed: Some(seed),
+            targets: true,
+            ..Config::default()
+        })
+    }
+
+    pub fn with_config(config: Config) -> Simulation {
+        let mut app = App::headless(&config);
+        app.set_mode(Mode::Play);
+        serve(&mut app);
+        Simulation { app }
+    }
+
+    pub fn tick(&mut self) {
+        self.app.on_tick();
+    }
+
+    // Returns true if the key would have quit the game
+    pub fn press(&mut self, code: KeyCode) -> bool {
+        apply_input(&mut self.app, Input::Key(code))
+    }
+
+    // Runs the game the way the event loop does with a replay, handing it each input from
+    // `source` once its step comes and ticking while the court is in play, for up to `ticks`
+    // ticks. Stops early once the game holds still, paused or in a menu, since the clock
+    // doesn't move there and no later input could ever come due. Returns true if an input
+    // would have quit the game
+    pub fn play(&mut self, source: &mut dyn InputSource, ticks: u64) -> bool {
+        for _ in 0..ticks {
+            while let Some(input) = source.next_input(self.app.history.step) {
+                if apply_input(&mut self.app, input) {
+                    return true;
+                }
+            }
+            let screen = self.app.screen();
+            if self.app.help || screen.clock(&self.app) != Clock::Game {
+                break;
+            }
+            screen.update(&mut self.app);
+        }
+        false
+    }
+
+    pub fn ball(&self) -> (f64, f64) {
+        let ball = &self.app.world[self.app.ball];
+        (ball.x, ball.y)
+    }
+
+    // Speed per tick along each axis, whichever way the ball is going
+    pub fn velocity(&self) -> (f64, f64) {
+        self.app.ball_speed()
+    }
+
+    pub fn ball_size(&self) -> (f64, f64) {
+        let ball = &self.app.world[self.app.ball];
+        (ball.width, ball.height)
+    }
+
+    pub fn playground(&self) -> Court {
+        self.app.playground
+    }
+
+    // Where the player's paddle is across the court
+    pub fn paddle(&self) -> f64 {
+        self.app.world[self.app.board].x
+    }
+
+    pub fn score(&self) -> u16 {
+        self.app.score
+    }
+
+    pub fn cpu_score(&self) -> u16 {
+        self.app.cpu_score
+    }
+
+    pub fn paused(&self) -> bool {
+        self.app.mode() == Mode::Paused
+    }
+
+    pub fn in_menu(&self) -> bool {
+        self.app.mode() == Mode::Menu
+    }
+
+    // The results screen is up, after a match and the celebration of a win
+    pub fn results_shown(&self) -> bool {
+        self.app.results.is_some()
+    }
+
+    pub fn game_over(&self) -> bool {
+        self.app.game_over()
+    }
+
+    // Throw the ball at any speed, past what the game would ever reach on its own
+    pub fn set_speed(&mut self, vx: f64, vy: f64, max_speed: f64) {
+        self.app.set_ball_speed(vx, vy);
+        self.app.max_speed = max_speed;
+    }
+
+    // Put the ball anywhere, heading down at the player's end
+    pub fn set_ball(&mut self, x: f64, y: f64) {
+        let ball = &mut self.app.world[self.app.ball];
+        ball.x = x;
+        ball.y = y;
+        self.app.turn_ball(None, Some(false));
+    }
+
+    pub fn set_ball_size(&mut self, width: f64, height: f64) {
+        let ball = &mut self.app.world[self.app.ball];
+        ball.width = width;
+        ball.height = height;
+    }
+
+    // Shrink or grow the court around its usual corner, paddles stay where they are
+    pub fn set_court(&mut self, width: u16, height: u16) {
+        self.app.playground = self.app.playground.resized(width, height);
+    }
+
+    // Swap left and right for the match the way the mirror modifier does
+    pub fn set_mirrored(&mut self, mirrored: bool) {
+        self.app.modifiers.mirror = mirrored;
+    }
+
+    // Jump straight to a score, e.g. to bring up the win screen on the next tick
+    pub fn set_score(&mut self, score: u16) {
+        self.app.score = score;
+    }
+
+    // Events from the most recent tick
+    pub fn events(&self) -> &[GameEvent] {
+        &self.app.events
+    }
+
+    pub fn ticks(&self) -> u64 {
+        self.app.history.step
+    }
+
+    pub fn app(&self) -> &App {
+        &self.app
+    }
+
+    // The game as the save file holds it, and picked up again from there
+    pub fn save(&self) -> io::Result<String> {
+        save::to_json(&self.app)
+    }
+
+    pub fn load(&mut self, json: &str) -> io::Result<()> {
+        save::from_json(&mut self.app, json)
+    }
+
+    pub fn render<R: Renderer>(&self, renderer: &mut R) -> io::Result<()> {
+        renderer.render(&self.app)
+    }
+}
+
+// What a batch of cpu against cpu games came to, for `simulate`
+#[derive(Default, Serialize)]
+pub struct Batch {
+    pub seed: u64,
+    pub games: u16,
+    pub player_wins: u16,
+    pub cpu_wins: u16,
+    pub unfinished: u16,
+    pub ticks: u64,
+    pub longest_game: u64, //in ticks
+    pub returns: u64,
+    pub rallies: u64,
+    pub longest_rally: u64,
+    pub top_speed: f64,
+    pub levels: u64,
+    pub highest_level: u64,
+}
+
+impl Batch {
+    pub fn report(&self, config: &Config) -> String {
+        let games = self.games.max(1) as f64;
+        format!(
+            "simulated {} games from seed {}, first to {}, {} difficulty\n\
+             player {}  cpu {}  unfinished {}\n\
+             game length  avg {:.0} ticks  longest {}\n\
+             rallies      avg {:.1} returns  longest {}\n\
+             ball speed   top {:.2}\n\
+             level        avg {:.1}  highest {}",
+            self.games,
+            self.seed,
+            config.win_score.unwrap_or(10),
+            format!("{:?}", config.difficulty.unwrap_or(Difficulty::Normal)).to_lowercase(),
+            self.player_wins,
+            self.cpu_wins,
+            self.unfinished,
+            self.ticks as f64 / games,
+            self.longest_game,
+            self.returns as f64 / self.rallies.max(1) as f64,
+            self.longest_rally,
+            self.top_speed,
+            self.levels as f64 / games,
+            self.highest_level
+        )
+    }
+}
+
+// Plays `config.games` games with the cpu on both paddles, each from its own seed counting
+// up from the given one so any single game can be played again
+pub(crate) fn simulate(config: &Config, seed: u64) -> Batch {
+    let mut batch = Batch {
+        seed,
+        ..Batch::default()
+    };
+    for game in 0..config.games.unwrap_or(100) {
+        let mut sim = Simulation::with_config(Config {
+            seed: Some(seed.wrapping_add(game as u64)),
+            ..config.clone()
+        });
+        sim.app.autopilot = true;
+        sim.app.settings.difficulty = config.difficulty.unwrap_or(Difficulty::Normal);
+
+        let mut rally = 0;
+        let winner = loop {
+            sim.tick();
+            let app = &sim.app;
+            let (vx, vy) = app.ball_speed();
+            batch.top_speed = batch.top_speed.max(vx.hypot(vy));
+            for event in &app.events {
+                match event {
+                    GameEvent::BallHitPaddle { paddle: Paddle::Player, .. } => rally += 1,
+                    GameEvent::PointScored | GameEvent::LifeLost => {
+                        batch.returns += rally;
+                        batch.rallies += 1;
+                        batch.longest_rally = batch.longest_rally.max(rally);
+                        rally = 0;
+                    }
+                    _ => {}
+                }
+            }
+            if app.win {
+                break Some(Winner::Player);
+            } else if app.lost || app.cpu_score >= app.win_score {
+                break Some(Winner::Cpu);
+            } else if app.tick_count >= MAX_TICKS {
+                break None;
+            }
+        };
+
+        match winner {
+            Some(Winner::Player) => batch.player_wins += 1,
+            Some(Winner::Cpu) => batch.cpu_wins += 1,
+            None => batch.unfinished += 1,
+        }
+        let ticks = sim.app.tick_count;
+        let level = sim.app.progress().level;
+        batch.games += 1;
+        batch.ticks += ticks;
+        batch.longest_game = batch.longest_game.max(ticks);
+        batch.levels += level;
+        batch.highest_level = batch.highest_level.max(level);
+    }
+    batch
+}
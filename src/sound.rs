@@ -0,0 +1,206 @@
+use std::io::{self, Write};
+#[cfg(feature = "audio")]
+use std::{fs, io::Cursor, path::Path, sync::Arc};
+
+#[cfg(feature = "audio")]
+use rodio::{source::ChannelVolume, Source};
+
+use log::warn;
+
+use crate::{
+    events::{GameEvent, Paddle},
+    series::Winner,
+};
+
+#[derive(Clone, Copy, Debug)]
+pub enum Clip {
+    Pong,
+    Victory,
+}
+
+// A file of the same name in here replaces the clip built into the binary
+#[cfg(feature = "audio")]
+pub const SOUNDS_DIR: &str = "assets/sounds";
+
+impl Clip {
+    #[cfg(feature = "audio")]
+    fn file_name(self) -> &'static str {
+        match self {
+            Clip::Pong => "pong.wav",
+            Clip::Victory => "victory.wav",
+        }
+    }
+
+    #[cfg(feature = "audio")]
+    fn embedded(self) -> &'static [u8] {
+        match self {
+            Clip::Pong => include_bytes!("../assets/pong.wav"),
+            Clip::Victory => include_bytes!("../assets/victory.wav"),
+        }
+    }
+
+    // The override from SOUNDS_DIR if rodio can decode it, the built in clip otherwise
+    #[cfg(feature = "audio")]
+    fn load(self) -> Arc<[u8]> {
+        let path = Path::new(SOUNDS_DIR).join(self.file_name());
+        match fs::read(&path) {
+            Ok(bytes) => {
+                let bytes: Arc<[u8]> = bytes.into();
+                match rodio::Decoder::new(Cursor::new(bytes.clone())) {
+                    Ok(_) => {
+                        log::info!("using {}", path.display());
+                        return bytes;
+                    }
+                    Err(err) => warn!("can't use {}, keeping the built in sound: {}", path.display(), err),
+                }
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => warn!("can't read {}, keeping the built in sound: {}", path.display(), err),
+        }
+        self.embedded().into()
+    }
+}
+
+// Anything that can play the game's clips
+pub trait AudioBackend {
+    // pitch scales the playback speed, 1.0 plays the clip as recorded. pan runs from
+    // -1.0 on the left speaker to 1.0 on the right
+    fn play(&self, clip: Clip, pitch: f32, pan: f32);
+    // 0.0 is silent, 1.0 plays the clips as recorded
+    fn set_volume(&self, volume: f32);
+}
+
+// Used for headless runs, --audio silent, and whenever no output device can be opened
+pub struct Silent;
+
+impl AudioBackend for Silent {
+    fn play(&self, _clip: Clip, _pitch: f32, _pan: f32) {}
+    fn set_volume(&self, _volume: f32) {}
+}
+
+// Rings the terminal bell instead, which still reaches the player over SSH
+pub struct Bell;
+
+impl AudioBackend for Bell {
+    fn play(&self, _clip: Clip, _pitch: f32, _pan: f32) {
+        let mut stdout = io::stdout();
+        // a lost beep is not worth interrupting the game for
+        let _ = stdout.write_all(b"\x07").and_then(|_| stdout.flush());
+    }
+
+    // the terminal decides how loud the bell is
+    fn set_volume(&self, _volume: f32) {}
+}
+
+// Pure Rust output through rodio/cpal, one sink per clip so effects never queue behind the music.
+// The clips are read once at startup and decoded from memory every time they play
+#[cfg(feature = "audio")]
+pub struct Rodio {
+    _stream: rodio::OutputStream,
+    pong: rodio::Sink,
+    victory: rodio::Sink,
+    pong_wav: Arc<[u8]>,
+    victory_wav: Arc<[u8]>,
+}
+
+#[cfg(feature = "audio")]
+impl Rodio {
+    pub fn new() -> Result<Rodio, String> {
+        let (_stream, handle) = rodio::OutputStream::try_default().map_err(|err| err.to_string())?;
+        let sink = || rodio::Sink::try_new(&handle).map_err(|err| err.to_string());
+
+        Ok(Rodio {
+            pong: sink()?,
+            victory: sink()?,
+            pong_wav: Clip::Pong.load(),
+            victory_wav: Clip::Victory.load(),
+            _stream,
+        })
+    }
+
+    fn sink(&self, clip: Clip) -> &rodio::Sink {
+        match clip {
+            Clip::Pong => &self.pong,
+            Clip::Victory => &self.victory,
+        }
+    }
+
+    fn wav(&self, clip: Clip) -> &Arc<[u8]> {
+        match clip {
+            Clip::Pong => &self.pong_wav,
+            Clip::Victory => &self.victory_wav,
+        }
+    }
+}
+
+#[cfg(feature = "audio")]
+impl AudioBackend for Rodio {
+    fn play(&self, clip: Clip, pitch: f32, pan: f32) {
+        // equal power panning, so a click in the middle is as loud as one at the side
+        let angle = (pan.clamp(-1.0, 1.0) + 1.0) * std::f32::consts::FRAC_PI_4;
+        match rodio::Decoder::new(Cursor::new(self.wav(clip).clone())) {
+            Ok(source) => {
+                let source = ChannelVolume::new(source.speed(pitch), vec![angle.cos(), angle.sin()]);
+                self.sink(clip).append(source);
+            }
+            Err(err) => warn!("can't play {}: {}", clip.file_name(), err),
+        }
+    }
+
+    fn set_volume(&self, volume: f32) {
+        self.pong.set_volume(volume);
+        self.victory.set_volume(volume);
+    }
+}
+
+pub const BACKENDS: [&str; 3] = ["rodio", "bell", "silent"];
+
+// Picks the backend named on the command line, rodio when built with the audio feature otherwise
+pub fn open(name: Option<&str>) -> Box<dyn AudioBackend> {
+    match name.unwrap_or(if cfg!(feature = "audio") { "rodio" } else { "silent" }) {
+        #[cfg(feature = "audio")]
+        "rodio" => match Rodio::new() {
+            Ok(rodio) => Box::new(rodio),
+            Err(err) => {
+                // without an output device the game simply runs silent
+                warn!("no audio output: {}", err);
+                Box::new(Silent)
+            }
+        },
+        "bell" => Box::new(Bell),
+        "silent" => Box::new(Silent),
+        other => {
+            warn!("audio backend '{}' is not available in this build", other);
+            Box::new(Silent)
+        }
+    }
+}
+
+// The audio side of the event bus: a click per paddle hit, pitched up with the rally
+// multiplier on the player's side, a high one for a target hit, a low one for a hit on a
+// boss, two quick ones for a full combo meter and the victory music when the player wins.
+// Every click also rises with the ball's speed, 1.0 at the speed a game starts with, and
+// comes from the side of the court the ball is on. `intensity` comes from the court's
+// ambience and lifts every clip, the music too, a little further each speed level
+pub fn play_events(audio: &dyn AudioBackend, events: &[GameEvent], speed: f64, pan: f64, intensity: f32) {
+    let speed = (1.0 + 0.15 * (speed.max(1.0) - 1.0) as f32) * intensity;
+    for event in events {
+        match *event {
+            GameEvent::BallHitPaddle { paddle, multiplier } => {
+                let pitch = match paddle {
+                    Paddle::Player => 1.0 + 0.12 * (multiplier - 1) as f32,
+                    Paddle::Cpu => 1.0,
+                };
+                audio.play(Clip::Pong, pitch * speed, pan as f32);
+            }
+            GameEvent::TargetHit => audio.play(Clip::Pong, 1.5 * speed, pan as f32),
+            GameEvent::BossHit(_) => audio.play(Clip::Pong, 0.7 * speed, pan as f32),
+            GameEvent::ComboFull => {
+                audio.play(Clip::Pong, 1.5 * speed, pan as f32);
+                audio.play(Clip::Pong, 2.0 * speed, pan as f32);
+            }
+            GameEvent::Win(Winner::Player) => audio.play(Clip::Victory, intensity, 0.0),
+            _ => {}
+        }
+    }
+}
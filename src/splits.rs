@@ -0,0 +1,58 @@
+use std::{fs, io, path::Path};
+
+// Time attack keeps one personal best per winning score, in the file named here
+pub fn file_name(win_score: u16) -> String {
+    format!("pong_terminal-{}.splits", win_score)
+}
+
+// Time of each point in a time attack run, the first time the score reached it, next
+// to the same split of the fastest winning run so far
+pub struct Splits {
+    pub times: Vec<f64>,
+    pub best: Vec<f64>,
+}
+
+impl Splits {
+    pub fn load(path: &Path) -> Splits {
+        let best = fs::read_to_string(path)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        Splits {
+            times: Vec::new(),
+            best,
+        }
+    }
+
+    // Called whenever a point is scored, a score won back after a miss is no new split
+    pub fn record(&mut self, score: u16, time: f64) -> Option<f64> {
+        if score as usize <= self.times.len() {
+            return None;
+        }
+        self.times.push(time);
+        Some(time)
+    }
+
+    // Ahead of the personal best when negative
+    pub fn delta(&self, split: usize) -> Option<f64> {
+        Some(self.times.get(split)? - self.best.get(split)?)
+    }
+
+    // Keeps the run as the new personal best if it won faster, returns whether it did
+    pub fn finish(&mut self, path: &Path) -> io::Result<bool> {
+        let faster = match (self.times.last(), self.best.last()) {
+            (Some(time), Some(best)) => time < best,
+            (Some(_), None) => true,
+            (None, _) => false,
+        };
+        if faster {
+            self.best = self.times.clone();
+            fs::write(path, serde_json::to_string(&self.best)?)?;
+        }
+        Ok(faster)
+    }
+
+    pub fn clear(&mut self) {
+        self.times.clear();
+    }
+}
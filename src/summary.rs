@@ -0,0 +1,84 @@
+use serde::Serialize;
+
+use crate::{locale::Locale, modifiers::Modifiers, series::Winner};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GameMode {
+    Classic,
+    Procedural,
+    Practice,
+    Hardcore,
+    Targets,
+    TimeAttack,
+    Rally,
+    Series(u16), //best of
+}
+
+// Something the game beat for the first time
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(tag = "record", content = "value", rename_all = "snake_case")]
+pub enum Record {
+    PersonalBest(f64), //time attack split file
+    FastestWin(f64),   //the profile's best winning time
+    BestRun(u16),      //the most points of any hardcore run on the seed
+}
+
+// How the last game went, printed to the shell once the terminal is back, as text or
+// as one line of JSON for scripts
+#[derive(Clone, Debug, Serialize)]
+pub struct Summary {
+    pub mode: GameMode,
+    pub winner: Option<Winner>, //None for a game left unfinished
+    pub score: u16,
+    pub cpu_score: u16,
+    pub win_score: u16,
+    pub time: f64,
+    pub level: u64,
+    pub records: Vec<Record>,
+    pub profile: Option<String>,
+    pub rating: Option<f64>, //the profile's rating afterwards
+    pub modifiers: Modifiers,
+}
+
+impl Summary {
+    pub fn lines(&self, locale: &Locale) -> Vec<String> {
+        let mode = match self.mode {
+            GameMode::Classic => locale.text("summary.classic"),
+            GameMode::Procedural => locale.text("summary.procedural"),
+            GameMode::Practice => locale.text("summary.practice"),
+            GameMode::Hardcore => locale.text("summary.hardcore"),
+            GameMode::Targets => locale.text("summary.targets"),
+            GameMode::TimeAttack => locale.text("summary.time_attack"),
+            GameMode::Rally => locale.text("summary.rally"),
+            GameMode::Series(best_of) => locale.format("summary.series", &[&best_of]),
+        };
+        let result = match self.winner {
+            Some(Winner::Player) => locale.text("summary.won"),
+            Some(Winner::Cpu) => locale.text("summary.lost"),
+            None => locale.text("summary.unfinished"),
+        };
+        let mut line = locale.format(
+            "summary",
+            &[&mode, &result, &self.score, &self.cpu_score, &format!("{:.1}", self.time), &self.level],
+        );
+        if !matches!(self.mode, GameMode::Practice | GameMode::Hardcore) {
+            line.push_str(&locale.format("summary.win_score", &[&self.win_score]));
+        }
+        let mut lines = vec![line];
+        if self.modifiers.any() {
+            lines.push(locale.format("summary.modifiers", &[&self.modifiers.describe(locale)]));
+        }
+        for record in &self.records {
+            lines.push(match record {
+                Record::PersonalBest(time) => locale.format("summary.personal_best", &[&format!("{:.2}", time)]),
+                Record::FastestWin(time) => locale.format("summary.fastest_win", &[&format!("{:.2}", time)]),
+                Record::BestRun(score) => locale.format("summary.best_run", &[score]),
+            });
+        }
+        if let (Some(name), Some(rating)) = (&self.profile, self.rating) {
+            lines.push(locale.format("summary.rating", &[name, &format!("{:.0}", rating)]));
+        }
+        lines
+    }
+}
@@ -0,0 +1,75 @@
+use std::ops::Range;
+
+use rand::Rng;
+use ratatui::{style::Color, widgets::canvas::Rectangle};
+
+use crate::{
+    app::TICK,
+    court::Court,
+    world::{Entity, Kind, World},
+};
+
+pub const TIME_LIMIT: u64 = (60_000 / TICK.as_millis()) as u64; //ticks, a minute on the game clock
+const SPAWN_EVERY: u64 = 75;
+const LIFETIME: u64 = 250; //how long a ring stays up unless it is hit
+const MAX_RINGS: usize = 3;
+const SIZE: f64 = 14.0;
+const ROWS: Range<f64> = Court::FULL.bottom() + 20.0..Court::FULL.top() - 25.0; //where rings come up, clear of both paddles
+
+// Target practice: rings come up in random spots every so often and go again after a
+// while. Sending the ball through one on its way up the court scores. The rings are the
+// world's, with a lifetime, this only keeps time for the next one
+pub struct Targets {
+    pub next_spawn: u64, //the tick the next ring comes up at
+}
+
+impl Targets {
+    pub fn new() -> Targets {
+        Targets { next_spawn: 0 }
+    }
+
+    pub fn clear(&mut self) {
+        self.next_spawn = 0;
+    }
+
+    pub fn time_left(tick: u64) -> u64 {
+        TIME_LIMIT.saturating_sub(tick)
+    }
+
+    // Brings a new ring up when one is due, and takes away any the ball is passing through
+    // while `rising`. Returns the hits
+    pub fn update<R: Rng>(&mut self, tick: u64, world: &mut World, ball: Entity, rising: bool, court: Court, rng: &mut R) -> u16 {
+        if tick >= self.next_spawn {
+            self.next_spawn = tick + SPAWN_EVERY;
+            if world.count(Kind::Ring) < MAX_RINGS {
+                let ring = world.spawn(Kind::Ring, spawn(court, rng), None);
+                world.expire_at(ring, tick + LIFETIME);
+            }
+        }
+        if !rising {
+            return 0;
+        }
+        let ball = world[ball].clone();
+        world.collide(Kind::Ring, |ring| through(ring, &ball).then_some(())).len() as u16
+    }
+}
+
+fn spawn<R: Rng>(court: Court, rng: &mut R) -> Rectangle {
+    let left = court.left();
+    let right = (court.right() - SIZE).max(left + 1.0);
+    Rectangle {
+        x: rng.gen_range(left..right).round(),
+        y: rng.gen_range(ROWS).round(),
+        width: SIZE,
+        height: SIZE,
+        color: Color::Reset,
+    }
+}
+
+// The middle of the ball inside the ring
+fn through(ring: &Rectangle, ball: &Rectangle) -> bool {
+    let radius = ring.width / 2.0;
+    let dx = ball.x + ball.width / 2.0 - (ring.x + radius);
+    let dy = ball.y + ball.height / 2.0 - (ring.y + radius);
+    dx * dx + dy * dy <= radius * radius
+}
@@ -0,0 +1,106 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+// One line of telemetry, velocities carry the direction of travel in their sign
+#[derive(Serialize)]
+pub struct Row {
+    pub tick: u64,
+    pub ball_x: f64,
+    pub ball_y: f64,
+    pub vx: f64,
+    pub vy: f64,
+    pub paddle_x: f64,
+    pub paddle_y: f64,
+    pub cpu_x: f64,
+    pub events: Vec<&'static str>,
+    pub split: Option<f64>, //time attack split taken this tick
+}
+
+// Per-tick recording of a game, written out when it ends as JSON for a .json path
+// and as CSV otherwise
+pub struct Telemetry {
+    pub path: PathBuf,
+    rows: Vec<Row>,
+}
+
+impl Telemetry {
+    pub fn new(path: &Path) -> Telemetry {
+        Telemetry {
+            path: path.to_path_buf(),
+            rows: Vec::new(),
+        }
+    }
+
+    pub fn record(&mut self, row: Row) {
+        self.rows.push(row);
+    }
+
+    pub fn clear(&mut self) {
+        self.rows.clear();
+    }
+
+    pub fn write(&self) -> io::Result<()> {
+        let mut file = io::BufWriter::new(fs::File::create(&self.path)?);
+        if self.path.extension().is_some_and(|ext| ext == "json") {
+            serde_json::to_writer(&mut file, &self.rows)?;
+        } else {
+            writeln!(file, "tick,ball_x,ball_y,vx,vy,paddle_x,paddle_y,cpu_x,events,split")?;
+            for row in &self.rows {
+                writeln!(
+                    file,
+                    "{},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{:.3},{},{}",
+                    row.tick,
+                    row.ball_x,
+                    row.ball_y,
+                    row.vx,
+                    row.vy,
+                    row.paddle_x,
+                    row.paddle_y,
+                    row.cpu_x,
+                    row.events.join(";"),
+                    row.split.map(|time| format!("{:.2}", time)).unwrap_or_default()
+                )?;
+            }
+        }
+        file.flush()
+    }
+}
+
+// The state of play once a second, for --stats-file
+#[derive(Serialize)]
+pub struct Sample {
+    pub time: f64,
+    pub tick: u64,
+    pub score: u16,
+    pub cpu_score: u16,
+    pub level: u64,
+    pub speed: f64, //ball speed in court units per tick
+    pub combo: u16,
+}
+
+// Samples written as JSON lines while the game runs, each flushed straight away so a
+// script can follow the file as it grows
+pub struct StatsStream {
+    pub path: PathBuf,
+    file: io::BufWriter<fs::File>,
+}
+
+impl StatsStream {
+    pub fn create(path: &Path) -> io::Result<StatsStream> {
+        Ok(StatsStream {
+            path: path.to_path_buf(),
+            file: io::BufWriter::new(fs::File::create(path)?),
+        })
+    }
+
+    pub fn write(&mut self, sample: &Sample) -> io::Result<()> {
+        serde_json::to_writer(&mut self.file, sample)?;
+        writeln!(self.file)?;
+        self.file.flush()
+    }
+}
@@ -0,0 +1,55 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+const SHOWN_FOR: Duration = Duration::from_secs(3);
+const MAX_WAITING: usize = 4; //past this the oldest waiting message is dropped
+
+// Short messages shown one after another in a corner of the screen, timed by the clock
+// rather than by ticks so they also go away on a paused or menu screen
+pub struct Toasts {
+    shown: Option<(String, Instant)>,
+    waiting: VecDeque<String>,
+}
+
+impl Toasts {
+    pub fn new() -> Toasts {
+        Toasts {
+            shown: None,
+            waiting: VecDeque::new(),
+        }
+    }
+
+    pub fn post(&mut self, text: String, now: Instant) {
+        if self.shown.is_none() {
+            self.shown = Some((text, now));
+            return;
+        }
+        if self.waiting.len() == MAX_WAITING {
+            self.waiting.pop_front();
+        }
+        self.waiting.push_back(text);
+    }
+
+    // Moves on to the next message once the current one has been up long enough, returns
+    // whether the screen needs drawing again
+    pub fn update(&mut self, now: Instant) -> bool {
+        match &self.shown {
+            Some((_, since)) if now >= *since + SHOWN_FOR => {
+                self.shown = self.waiting.pop_front().map(|text| (text, now));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn current(&self) -> Option<&str> {
+        self.shown.as_ref().map(|(text, _)| text.as_str())
+    }
+
+    // When the message on show is due to go
+    pub fn due(&self) -> Option<Instant> {
+        self.shown.as_ref().map(|(_, since)| *since + SHOWN_FOR)
+    }
+}
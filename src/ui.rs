@@ -0,0 +1,1101 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    symbols::Marker,
+    widgets::{canvas::{Canvas, Context, Painter, Rectangle, Shape}, Block, Borders, Clear, Gauge, Paragraph, Sparkline, Widget, Wrap},
+    Frame,
+};
+
+use crate::{
+    app::{self, App, Mode, Orientation},
+    court::Court,
+    events::Paddle,
+    input,
+    level::{self, BrickKind},
+    results::{Results, CELEBRATION_TICKS},
+    settings::{Acceleration, Background, Difficulty, MarkerStyle, Row, ROWS},
+    series::Winner,
+    splits::Splits,
+    targets::Targets,
+    world::Kind,
+};
+
+pub(crate) mod animation;
+mod backdrop;
+mod bigtext;
+mod halfblock;
+mod round;
+
+use backdrop::{Grid, Shade, Starfield};
+use bigtext::BigText;
+use halfblock::HalfBlocks;
+use round::{Capsule, Disc};
+
+// Smallest terminal that still fits the court and both gauges
+pub const MIN_WIDTH: u16 = 40;
+pub const MIN_HEIGHT: u16 = 12;
+
+pub fn fits(size: Rect) -> bool {
+    size.width >= MIN_WIDTH && size.height >= MIN_HEIGHT
+}
+
+// Block cells change far less often than braille dots as things move, so fewer bytes go out
+fn marker(app: &App) -> Marker {
+    if app.low_bandwidth || app.monochrome {
+        return Marker::Block;
+    }
+    match app.settings.marker {
+        MarkerStyle::Braille => Marker::Braille,
+        MarkerStyle::Block => Marker::Block,
+        MarkerStyle::Dot => Marker::Dot,
+    }
+}
+
+// Score gauge fill for each rally multiplier, plain white outside rally scoring
+const COMBO_COLORS: [Color; 4] = [Color::White, Color::Yellow, Color::LightGreen, Color::LightCyan];
+const METER_WIDTH: u16 = 12; //the combo meter beside the score
+
+// Sideways play swaps the court axes on the way to the screen and a rematch turns the court
+// upside down, colours come from the palette
+fn oriented(app: &App, rect: &Rectangle, color: Color) -> Rectangle {
+    let flipped;
+    let rect = if app.flipped {
+        let court = Court::FULL;
+        flipped = Rectangle {
+            y: court.bottom() + court.top() - rect.y - rect.height,
+            ..rect.clone()
+        };
+        &flipped
+    } else {
+        rect
+    };
+    match app.orientation {
+        Orientation::Upright => Rectangle { color, ..rect.clone() },
+        Orientation::Sideways => Rectangle {
+            x: rect.y,
+            y: rect.x,
+            width: rect.height,
+            height: rect.width,
+            color,
+        },
+    }
+}
+
+// A rectangle with every dot inside it set, unlike the outline ratatui draws. Half off the
+// court it falls back to the outline
+struct Solid(Rectangle);
+
+impl Shape for Solid {
+    fn draw(&self, painter: &mut Painter) {
+        let rect = &self.0;
+        if !bigtext::fill(painter, rect.x, rect.y, (rect.width, rect.height), rect.color) {
+            rect.draw(painter);
+        }
+    }
+}
+
+// What the court paints, bottom first. Each is a canvas layer of its own, so a cell shows
+// the topmost thing in it instead of a mix of their dots
+#[derive(Clone, Copy)]
+enum Layer {
+    Backdrop,
+    Zones,
+    Banner,
+    Play, //bricks, ball and paddles
+}
+
+const LAYERS: [Layer; 4] = [Layer::Backdrop, Layer::Zones, Layer::Banner, Layer::Play];
+
+// Stars and grid go on slow links, the stars hold still for reduced motion
+fn backdrop(app: &App, ctx: &mut Context) {
+    if app.low_bandwidth {
+        return;
+    }
+    let orientation = app.orientation;
+    match app.settings.background {
+        Background::Off => {}
+        Background::Stars => {
+            let offset = if app.reduced_motion { 0.0 } else { app.tick_count as f64 * 0.1 };
+            ctx.draw(&Starfield { offset, orientation });
+        }
+        Background::Grid => ctx.draw(&Grid { orientation }),
+    }
+}
+
+// Where the court is drawn and the canvas bounds it shows. Once the walls close in the
+// court keeps its scale and gets narrower, so the border moves in with the walls
+fn court_area(app: &App, area: Rect) -> (Rect, [f64; 2], [f64; 2]) {
+    let (full, playground) = (Court::FULL, app.playground);
+    let (across, along) = (playground.x_bounds(), full.y_bounds());
+    let squeeze = |cells: u16| {
+        let inner = cells.saturating_sub(2);
+        let kept = (inner as f64 * playground.width() / full.width()).round() as u16;
+        ((inner - kept.min(inner)) / 2, kept + 2)
+    };
+    match app.orientation {
+        Orientation::Upright => {
+            let (offset, width) = squeeze(area.width);
+            (Rect { x: area.x + offset, width: width.min(area.width), ..area }, across, along)
+        }
+        Orientation::Sideways => {
+            let (offset, height) = squeeze(area.height);
+            (Rect { y: area.y + offset, height: height.min(area.height), ..area }, along, across)
+        }
+    }
+}
+
+// Zones are part of play, so unlike the backdrop they are drawn on slow links too
+fn zones(app: &App, ctx: &mut Context) {
+    let Some(level) = &app.level else {
+        return;
+    };
+    for zone in &level.zones {
+        ctx.draw(&Shade { rect: oriented(app, &zone.rect, app.palette.zone), kind: zone.kind });
+    }
+}
+
+// Court above the HUD, or the court alone in zen mode
+fn screen_chunks(area: Rect, zen: bool) -> Vec<Rect> {
+    if zen {
+        return vec![area];
+    }
+    Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(75), Constraint::Percentage(25)])
+        .split(area)
+        .to_vec()
+}
+
+// Maps a terminal cell to canvas coordinates, if it lies inside the court
+pub fn canvas_point(size: Rect, column: u16, row: u16) -> Option<(f64, f64)> {
+    let court = screen_chunks(size, false)[0].inner(Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    Court::FULL.point_at(court, column, row)
+}
+
+// The HUD as last drawn and everything it showed. Most frames nothing in it changes, so the
+// cells are copied back instead of laying out and painting the gauges again
+#[derive(Default)]
+pub struct HudCache {
+    key: String,
+    cells: Buffer,
+    pub reused: bool, //whether the last frame got its HUD from here
+}
+
+// Copies cells drawn earlier into the same place on this frame
+struct Cached<'a>(&'a Buffer);
+
+impl Widget for Cached<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = area.intersection(self.0.area);
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                buf[(x, y)] = self.0[(x, y)].clone();
+            }
+        }
+    }
+}
+
+pub fn ui(f: &mut Frame, app: &App, hud: &mut HudCache) {
+    if !fits(f.area()) {
+        too_small_ui(f, app);
+    } else {
+        app.screen().render(f, app, hud);
+
+        if app.help {
+            help_ui(f, app);
+        }
+        if app.debug.visible {
+            debug_ui(f, app);
+        }
+        toast_ui(f, app);
+    }
+
+    if app.monochrome {
+        f.render_widget(Uncoloured, f.area());
+    }
+}
+
+// Drops every colour from what has been drawn so far, styles like bold stay
+struct Uncoloured;
+
+impl Widget for Uncoloured {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        for x in area.left()..area.right() {
+            for y in area.top()..area.bottom() {
+                buf[(x, y)].set_fg(Color::Reset).set_bg(Color::Reset);
+            }
+        }
+    }
+}
+
+// Without colours the empty part of a gauge is blank cells that run into its label, so the
+// gauge is spelled out in shade characters instead. `ratio` is clamped to 0-1, ratatui
+// panics outside it
+fn gauge(buf: &mut Buffer, app: &App, area: Rect, block: Block, ratio: f64, label: String, style: Style) {
+    let ratio = if ratio.is_nan() { 0.0 } else { ratio.clamp(0.0, 1.0) };
+    if !app.monochrome {
+        let gauge = Gauge::default().block(block).gauge_style(style).ratio(ratio).label(label);
+        gauge.render(area, buf);
+        return;
+    }
+
+    let inner = block.inner(area);
+    let filled = ((inner.width as f64 * ratio) as usize).min(inner.width as usize);
+    let lines = (0..inner.height)
+        .map(|row| {
+            let mut bar: Vec<char> = "█".repeat(filled).chars().chain("░".repeat(inner.width as usize - filled).chars()).collect();
+            if row == inner.height / 2 {
+                let start = (bar.len().saturating_sub(label.chars().count())) / 2;
+                for (cell, c) in bar.iter_mut().skip(start).zip(label.chars()) {
+                    *cell = c;
+                }
+            }
+            Line::from(bar.into_iter().collect::<String>())
+        })
+        .collect::<Vec<_>>();
+    Paragraph::new(lines).block(block).render(area, buf);
+}
+
+pub(crate) fn game_ui(f: &mut Frame, app: &App, cache: &mut HudCache) {
+    let mut chunks = screen_chunks(f.area(), app.zen);
+    if let (Some(splits), false) = (&app.splits, app.zen) {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(SPLITS_WIDTH)].as_ref())
+            .split(chunks[0]);
+        chunks[0] = columns[0];
+        splits_ui(f, app, splits, columns[1]);
+    }
+
+    let locale = &app.locale;
+    let (ball, board) = (&app.world[app.ball], &app.world[app.board]);
+    let title = match &app.level {
+        _ if app.mode() == Mode::Demo => locale.text("title.demo"),
+        _ if app.mode() == Mode::InstantReplay => locale.text("title.instant_replay"),
+        _ if app.hardcore => locale.format("title.hardcore", &[&app.history.seed]),
+        _ if app.targets.is_some() => locale.text("title.targets"),
+        _ if app.practice => locale.format(
+            "title.practice",
+            &[&board.width, &board.height, &ball.width, &ball.height],
+        ),
+        Some(level) if app.boss.is_some() => locale.format("title.boss", &[&level.index]),
+        Some(level) => locale.format("title.stage", &[&level.index]),
+        None => locale.text("title"),
+    };
+    let title = match &app.series {
+        Some(series) if app.mode() != Mode::Demo => locale.format(
+            "title.series",
+            &[&title, &series.game().min(series.best_of), &series.player, &series.cpu],
+        ),
+        _ => title,
+    };
+    let title = if app.modifiers.any() && app.mode() != Mode::Demo {
+        format!("{} - {}", title, app.modifiers.describe(locale))
+    } else {
+        title
+    };
+    let title = match app.mod_hud.as_str() {
+        "" => title,
+        hud => format!("{} - {}", title, hud),
+    };
+    // the key only works for a moment of play after a point, so the hint lasts as long
+    let title = if matches!(app.mode(), Mode::Play | Mode::Paused) && app.instant.offered(app.tick_count) {
+        format!("{} - {}", title, locale.text("notice.instant_replay"))
+    } else {
+        title
+    };
+    let (area, x_bounds, y_bounds) = court_area(app, chunks[0]);
+    let banner = match app.mode() {
+        Mode::Play | Mode::Paused if app.game_over() && app.hardcore => Some(locale.text("banner.hardcore")),
+        Mode::Play | Mode::Paused if app.game_over() => Some(locale.text(if app.win { "banner.win" } else { "banner.lose" })),
+        _ => None,
+    };
+    let court = area.inner(Margin {
+        vertical: 1,
+        horizontal: 1,
+    });
+    let palette = &app.palette;
+    let banner = banner.filter(|text| bigtext::legible(text, court, marker(app), 0.3));
+    // the outlined ball is itself a cue in some palettes, so only a filled one is smoothed
+    let smooth_ball = app.settings.half_blocks && (app.catchable || !palette.solid_catchable);
+    let ball_color = if app.catchable { palette.catchable } else { palette.ball };
+    // the court changes colour with the speed level, a tint is too much to send on a slow link
+    let ambience = palette.ambience(app.progress().level);
+    let tint = if app.low_bandwidth { Color::Reset } else { ambience.tint };
+    let canvas = Canvas::default()
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(ambience.border)).title(title))
+        .background_color(tint)
+        .marker(marker(app))
+        .paint(|ctx| {
+            for layer in LAYERS {
+                match layer {
+                    Layer::Backdrop => backdrop(app, ctx),
+                    Layer::Zones => zones(app, ctx),
+                    Layer::Banner => {
+                        if let Some(text) = &banner {
+                            let band = (y_bounds[1] - y_bounds[0]) * 0.15;
+                            let (hop, color) = celebration(app);
+                            let middle = (y_bounds[0] + y_bounds[1]) / 2.0 + hop * band;
+                            ctx.draw(&BigText::fit(text, x_bounds, [middle - band, middle + band], 0.8, color));
+                        }
+                    }
+                    Layer::Play => play_layer(app, ctx, smooth_ball, ball_color),
+                }
+                ctx.layer();
+            }
+        });
+    f.render_widget(canvas.x_bounds(x_bounds).y_bounds(y_bounds), area);
+    if app.settings.half_blocks {
+        let mut rects = vec![
+            oriented(app, &paddle(app, Paddle::Player), paddle_color(app)),
+            oriented(app, &paddle(app, Paddle::Cpu), palette.paddle),
+        ];
+        if smooth_ball && app.shown(Kind::Ball) {
+            rects.push(oriented(app, &squashed(app, &app.world[app.ball], app.impacts.ball(app.tick_count)), ball_color));
+        }
+        f.render_widget(HalfBlocks { rects: &rects, x_bounds, y_bounds }, court);
+    }
+
+    cache.reused = false;
+    if let Some(&hud) = chunks.get(1) {
+        let key = hud_key(app, hud);
+        cache.reused = key == cache.key;
+        if !cache.reused {
+            cache.cells = Buffer::empty(hud);
+            hud_ui(&mut cache.cells, app, hud);
+            cache.key = key;
+        }
+        f.render_widget(Cached(&cache.cells), hud);
+    }
+}
+
+fn play_layer(app: &App, ctx: &mut Context, smooth_ball: bool, ball_color: Color) {
+    let palette = &app.palette;
+    let mut entities: Vec<_> = app.world.entities().filter(|&(_, kind)| app.shown(kind)).collect();
+    entities.sort_by_key(|&(_, kind)| depth(kind));
+    for (entity, kind) in entities {
+        let body = &app.world[entity];
+        match kind {
+            Kind::Brick(brick) => {
+                let row = level::cell_of(body).map_or(0, |(row, _)| row);
+                let rect = oriented(app, body, palette.brick(brick, row));
+                if brick == BrickKind::Obstacle && palette.solid_obstacles {
+                    ctx.draw(&Solid(rect));
+                } else {
+                    ctx.draw(&rect);
+                }
+            }
+            Kind::Boss => ctx.draw(&Capsule { rect: oriented(app, body, palette.boss), filled: true }),
+            Kind::ExtraBall => ctx.draw(&Disc { rect: oriented(app, body, palette.boss), filled: true }),
+            Kind::Ring => ctx.draw(&Disc { rect: oriented(app, body, palette.target), filled: false }),
+            Kind::Ball if !smooth_ball => {
+                let filled = app.catchable && palette.solid_catchable;
+                let rect = squashed(app, body, app.impacts.ball(app.tick_count));
+                ctx.draw(&Disc { rect: oriented(app, &rect, ball_color), filled });
+            }
+            Kind::Shadow => ctx.draw(&Disc { rect: oriented(app, body, palette.decoy), filled: false }),
+            // a paying combo meter fills the player's paddle in, so it shows without colour too
+            Kind::Paddle(Paddle::Player) if !app.settings.half_blocks => {
+                ctx.draw(&Capsule { rect: oriented(app, &paddle(app, Paddle::Player), paddle_color(app)), filled: app.meter.charged() })
+            }
+            Kind::Paddle(Paddle::Cpu) if !app.settings.half_blocks => {
+                ctx.draw(&Capsule { rect: oriented(app, &paddle(app, Paddle::Cpu), palette.paddle), filled: false })
+            }
+            Kind::Ball | Kind::Paddle(_) => {} //in half blocks over the canvas
+        }
+    }
+}
+
+// Which of the world's entities are drawn over which, the bricks and the boss under the
+// rest, the balls over the rings they go through and the paddles over everything
+fn depth(kind: Kind) -> u8 {
+    match kind {
+        Kind::Brick(_) => 0,
+        Kind::Boss => 1,
+        Kind::ExtraBall => 2,
+        Kind::Ring => 3,
+        Kind::Ball => 4,
+        Kind::Shadow => 5,
+        Kind::Paddle(_) => 6,
+    }
+}
+
+// A paddle as drawn, squashed for a few ticks after the ball hits it
+fn paddle(app: &App, paddle: Paddle) -> Rectangle {
+    let rect = match paddle {
+        Paddle::Player => &app.world[app.board],
+        Paddle::Cpu => &app.world[app.cpu],
+    };
+    squashed(app, rect, app.impacts.paddle(paddle, app.tick_count))
+}
+
+// Nothing springs about with reduced motion
+fn squashed(app: &App, rect: &Rectangle, scale: (f64, f64)) -> Rectangle {
+    if app.reduced_motion {
+        rect.clone()
+    } else {
+        animation::scaled(rect, scale)
+    }
+}
+
+fn paddle_color(app: &App) -> Color {
+    if app.meter.charged() {
+        app.palette.glow
+    } else {
+        app.palette.paddle
+    }
+}
+
+// Level progress and the countdown to the next speed-up, in whole tens and whole seconds
+// on a slow link so the gauge changes less often. Just the seconds if the sentence won't
+// fit in `width`
+fn level_gauge(app: &App, width: u16) -> (f64, String) {
+    let progress = app.progress();
+    let secs = progress.remaining_secs(app::TICK);
+    let (ratio, secs) = if app.low_bandwidth {
+        ((progress.percent() / 10 * 10) as f64 / 100.0, format!("{}", secs.ceil()))
+    } else {
+        (progress.ratio(), format!("{:.1}", secs))
+    };
+    let label = app.locale.format("level.next", &[&secs]);
+    if label.chars().count() > width as usize {
+        (ratio, format!("{}s", secs))
+    } else {
+        (ratio, label)
+    }
+}
+
+// Everything hud_ui draws from, written out so two frames can be compared
+fn hud_key(app: &App, area: Rect) -> String {
+    let look = (app.monochrome, marker(app), app.palette, app.locale.text("score"));
+    if app.game_over() {
+        let blink = result_flash(app);
+        format!("{:?} {:?} {} {} {:?} {}", area, look, game_result(app), blink, app.streamdata, app.win_time)
+    } else {
+        let series = app.series.is_some().then_some(app.cpu_score);
+        let rally = app.rally.then_some(app.combo);
+        let time_left = app.targets.is_some().then(|| time_left(app));
+        let boss = app.boss.as_ref().map(|boss| boss.health);
+        let meter = (app.meter.charged(), app.meter.percent());
+        format!(
+            "{:?} {:?} {}/{} {:?} {:?} {:?} {:?} {:?} {} {} {}",
+            area,
+            look,
+            app.score,
+            app.win_score,
+            series,
+            rally,
+            time_left,
+            boss,
+            meter,
+            app.speed,
+            app.progress().level,
+            level_gauge(app, area.width).1
+        )
+    }
+}
+
+// The result flashes on and off, counting the celebration too as the game clock stops
+// for it
+fn result_flash(app: &App) -> bool {
+    (app.tick_count + app.celebration) & 0x20 == 0x20 && !app.low_bandwidth && !app.reduced_motion
+}
+
+// Whole seconds left of target practice
+fn time_left(app: &App) -> u64 {
+    (Targets::time_left(app.tick_count) as f64 * app::TICK.as_secs_f64()).ceil() as u64
+}
+
+// Score and level gauges during a game, the result and time once it is over
+fn hud_ui(buf: &mut Buffer, app: &App, area: Rect) {
+    let locale = &app.locale;
+    let palette = &app.palette;
+    let bottom_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(80), Constraint::Percentage(20)].as_ref())
+        .split(area);
+
+    if !app.game_over() {
+        let mut label = if app.hardcore {
+            locale.format("score.hardcore", &[&app.score])
+        } else if app.endless {
+            locale.format("score.endless", &[&app.score])
+        } else {
+            format!("{}/{}", app.score, app.win_score)
+        };
+        if app.series.is_some() {
+            label = format!("{}  {}", label, locale.format("score.cpu", &[&app.cpu_score, &app.win_score]));
+        }
+        if app.rally {
+            label = format!("{}  {}", label, locale.format("score.combo", &[&app.combo, &app.multiplier()]));
+        }
+        if app.speed != 1.0 {
+            label = format!("{}  {}", label, locale.format("score.speed", &[&app.speed]));
+        }
+        if app.targets.is_some() {
+            label = format!("{}  {}", label, locale.format("score.time_left", &[&time_left(app)]));
+        }
+        let score_chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(METER_WIDTH)].as_ref())
+            .split(bottom_chunks[0]);
+        gauge(
+            buf,
+            app,
+            score_chunks[0],
+            Block::default().title(locale.text("score")).borders(Borders::ALL),
+            app.score as f64 / app.win_score.max(1) as f64,
+            label,
+            Style::default().fg(COMBO_COLORS[app.multiplier() as usize - 1]).bg(palette.score_track),
+        );
+        let meter = &app.meter;
+        let label = if meter.charged() {
+            locale.format("combo.boost", &[&meter.multiplier()])
+        } else {
+            format!("{}%", meter.percent())
+        };
+        gauge(
+            buf,
+            app,
+            score_chunks[1],
+            Block::default().title(locale.text("combo")).borders(Borders::ALL),
+            meter.percent() as f64 / 100.0,
+            label,
+            Style::default().fg(if meter.charged() { palette.glow } else { Color::Magenta }),
+        );
+
+        // a boss's health takes the place of the level gauge until it is beaten
+        if let Some(boss) = &app.boss {
+            gauge(
+                buf,
+                app,
+                bottom_chunks[1],
+                Block::default().title(locale.text("boss")).borders(Borders::LEFT | Borders::RIGHT),
+                boss.health as f64 / boss.max_health.max(1) as f64,
+                format!("{}/{}", boss.health, boss.max_health),
+                Style::default().fg(palette.boss),
+            );
+        } else {
+            let (ratio, label) = level_gauge(app, bottom_chunks[1].width.saturating_sub(2));
+            gauge(
+                buf,
+                app,
+                bottom_chunks[1],
+                Block::default().title(locale.format("level", &[&app.progress().level])).borders(Borders::LEFT | Borders::RIGHT),
+                ratio,
+                label,
+                Style::default().fg(Color::Cyan),
+            );
+        }
+
+    }else{
+        let result = game_result(app);
+        // the flashing repaints the whole sparkline, so it stays steady on slow links
+        if result_flash(app) {
+            let sparkline = Sparkline::default()
+                .block(
+                    Block::default()
+                    .title(result.as_str())
+                    .borders(Borders::ALL)
+                )
+                .data(&app.streamdata)
+                .style(Style::default().fg(Color::LightYellow));
+            sparkline.render(bottom_chunks[0], buf);
+        } else {
+            let sparkline = Sparkline::default()
+                .block(
+                    Block::default()
+                    .title(result.as_str())
+                    .borders(Borders::ALL)
+                )
+                .data(&app.streamdata)
+                .style(Style::default().fg(Color::Yellow));
+            sparkline.render(bottom_chunks[0], buf);
+        }
+
+        let time = format!("{}", app.win_time);
+        let block = Block::default().borders(Borders::LEFT | Borders::RIGHT).title(locale.text("timer"));
+        let big = bigtext::legible(&time, block.inner(bottom_chunks[1]), marker(app), 0.9);
+        let canvas = Canvas::default()
+            .block(block)
+            .marker(marker(app))
+            .paint(|ctx| {
+                if big {
+                    ctx.draw(&BigText::fit(&time, [0.0, 50.0], [0.0, 50.0], 0.9, Color::Yellow));
+                } else {
+                    ctx.print(5.0, 25.0, Span::styled(time.clone(), Style::default().fg(Color::Yellow)));
+                }
+            })
+            .x_bounds([0.0, 50.0])
+            .y_bounds([0.0, 50.0]);
+        canvas.render(bottom_chunks[1], buf);
+    }
+}
+
+const SPLITS_WIDTH: u16 = 20;
+
+// Time attack splits, newest at the bottom, coloured by whether they beat the best run
+fn splits_ui(f: &mut Frame, app: &App, splits: &Splits, area: Rect) {
+    let locale = &app.locale;
+    let time = if app.game_over() { app.win_time } else { app.elapsed() };
+    let rows = area.height.saturating_sub(3) as usize; //borders and the best time
+    let skip = splits.times.len().saturating_sub(rows);
+    let mut lines: Vec<Line> = splits
+        .times
+        .iter()
+        .enumerate()
+        .skip(skip)
+        .map(|(i, split)| {
+            let mut spans = vec![Span::raw(format!("{:>3} {:>6.2}", i + 1, split))];
+            if let Some(delta) = splits.delta(i) {
+                let color = if delta < 0.0 { Color::Green } else { Color::Red };
+                spans.push(Span::styled(format!(" {:+6.2}", delta), Style::default().fg(color)));
+            }
+            Line::from(spans)
+        })
+        .collect();
+    if let Some(best) = splits.best.last() {
+        lines.push(Line::from(Span::styled(
+            locale.format("splits.best", &[&format!("{:.2}", best)]),
+            Style::default().fg(Color::Yellow),
+        )));
+    }
+    let title = locale.format("splits", &[&format!("{:.1}", time)]);
+    f.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(title)), area);
+}
+
+fn game_result(app: &App) -> String {
+    let locale = &app.locale;
+    if app.hardcore {
+        return match app.run_rank {
+            Some((rank, runs)) => locale.format("result.hardcore", &[&app.score, &rank, &runs, &app.history.seed]),
+            None => locale.format("score.hardcore", &[&app.score]),
+        };
+    }
+    let game = locale.text(if app.win { "result.win" } else { "result.lose" });
+    match &app.series {
+        Some(series) => match series.winner() {
+            Some(Winner::Player) => locale.format("result.series_win", &[&series.player, &series.cpu]),
+            Some(Winner::Cpu) => locale.format("result.series_lose", &[&series.cpu, &series.player]),
+            None => locale.format("result.next_game", &[&game, &series.game()]),
+        },
+        None if app.can_keep_playing() => locale.format("result.keep_playing", &[&game]),
+        None => game,
+    }
+}
+
+pub(crate) fn menu_ui(f: &mut Frame, app: &App) {
+    let title = match &app.profile {
+        Some(profile) => app.locale.format("title.profile", &[&profile.name]),
+        None => app.locale.text("title"),
+    };
+    let block = Block::default().borders(Borders::ALL).title(title);
+    let inner = block.inner(f.area());
+    f.render_widget(block, f.area());
+
+    let logo = Rect {
+        height: (inner.height / 2).min(12),
+        ..inner
+    };
+    let mut lines = Vec::new();
+    if bigtext::legible("PONG", logo, marker(app), 0.5) {
+        f.render_widget(logo_canvas(app), logo);
+    } else {
+        lines = vec![Line::from(""), Line::from("P O N G"), Line::from("")];
+    }
+    let area = if lines.is_empty() {
+        Rect {
+            y: logo.bottom(),
+            height: inner.height - logo.height,
+            ..inner
+        }
+    } else {
+        inner
+    };
+    for (i, item) in app.menu.items().iter().enumerate() {
+        let style = if i == app.menu.selected {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(Span::styled(app.locale.text(item.key()), style)));
+    }
+
+    let paragraph = Paragraph::new(lines).alignment(Alignment::Center);
+    f.render_widget(paragraph, area);
+}
+
+// Each letter hops and changes colour on its own beat, the word itself takes half the space.
+// With reduced motion the letters sit still in their first colours
+fn logo_canvas(app: &App) -> Canvas<'_, impl Fn(&mut Context)> {
+    let frame = if app.reduced_motion { 0 } else { app.menu.frame };
+    Canvas::default()
+        .marker(marker(app))
+        .paint(move |ctx| {
+            let word = BigText::fit("PONG", [0.0, 100.0], [0.0, 70.0], 0.5, Color::Reset);
+            for (i, c) in ["P", "O", "N", "G"].into_iter().enumerate() {
+                ctx.draw(&BigText {
+                    text: c,
+                    x: word.x + (i * 4) as f64 * word.pixel.0,
+                    y: word.y + animation::bounce(frame, i) * 30.0,
+                    pixel: word.pixel,
+                    color: animation::cycle(frame, i),
+                });
+            }
+        })
+        .x_bounds([0.0, 100.0])
+        .y_bounds([0.0, 100.0])
+}
+
+pub(crate) fn profiles_ui(f: &mut Frame, app: &App) {
+    let locale = &app.locale;
+    let profiles = &app.profiles;
+    let highlight = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
+    let mut lines = vec![Line::from("")];
+    for (i, (name, stats)) in profiles.names.iter().zip(&profiles.stats).enumerate() {
+        let mut line = format!(
+            "{:<16}  {}  {}",
+            name,
+            locale.format("profiles.rating", &[&format!("{:.0}", stats.rating)]),
+            locale.format("profiles.stats", &[&stats.wins, &stats.losses])
+        );
+        if let Some(best) = stats.best_time {
+            line = format!("{}  {}", line, locale.format("profiles.best", &[&best]));
+        }
+        let style = if i == profiles.selected && profiles.name.is_none() { highlight } else { Style::default() };
+        lines.push(Line::from(Span::styled(line, style)));
+    }
+    lines.push(Line::from(""));
+    let keys = match &profiles.name {
+        Some(name) => {
+            lines.push(Line::from(Span::styled(locale.format("profiles.name", &[name]), highlight)));
+            locale.text("profiles.typing")
+        }
+        None => {
+            let style = if profiles.selected == profiles.names.len() { highlight } else { Style::default() };
+            lines.push(Line::from(Span::styled(locale.text("profiles.new"), style)));
+            locale.text("profiles.keys")
+        }
+    };
+    lines.push(Line::from(""));
+    lines.push(Line::from(keys));
+
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(locale.text("profiles")))
+        .alignment(Alignment::Center);
+    f.render_widget(paragraph, f.area());
+}
+
+fn too_small_ui(f: &mut Frame, app: &App) {
+    let size = f.area();
+    let text = vec![
+        Line::from(""),
+        Line::from(app.locale.text("too_small")),
+        Line::from(app.locale.format("too_small.need", &[&MIN_WIDTH, &MIN_HEIGHT])),
+        Line::from(app.locale.format("too_small.current", &[&size.width, &size.height])),
+    ];
+    let paragraph = Paragraph::new(text)
+        .style(Style::default().fg(Color::Yellow))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(paragraph, size);
+}
+
+fn debug_ui(f: &mut Frame, app: &App) {
+    let size = f.area();
+    let area = Rect::new(size.right().saturating_sub(30), size.y, 30.min(size.width), 11.min(size.height));
+    let bricks = app.world.entities().filter(|(_, kind)| matches!(kind, Kind::Brick(_))).count();
+    let text = vec![
+        Line::from(match app.max_fps {
+            0 => format!("fps    {:.1}", app.debug.fps),
+            cap => format!("fps    {:.1} (cap {})", app.debug.fps, cap),
+        }),
+        Line::from(match (app.debug.steps, app.tick_scale) {
+            (0 | 1, 1) => format!("tick   {:?}", app.debug.tick_time),
+            (steps, 1) => format!("tick   {:?} (x{})", app.debug.tick_time, steps),
+            (steps, scale) => format!("tick   {:?} (x{} of {} ticks)", app.debug.tick_time, steps, scale),
+        }),
+        Line::from(format!("poll   {:?}", app.debug.poll_latency)),
+        Line::from(format!("draw   {:?}{}", app.debug.draw_time, if app.low_bandwidth { " (low bw)" } else { "" })),
+        Line::from(format!("cells  {} ({} B)", app.debug.output.cells, app.debug.output.bytes)),
+        Line::from(format!(
+            "out    {:.1} KB/s{}",
+            app.debug.bytes_per_second / 1024.0,
+            if app.debug.output.hud_reused { " (hud kept)" } else { "" }
+        )),
+        Line::from(format!("ball v {:.2}, {:.2} (rx {:+.1})", app.ball_speed().0, app.ball_speed().1, app.rx)),
+        Line::from(format!(
+            "balls {}  paddles {}  bricks {}",
+            [Kind::Ball, Kind::Shadow, Kind::ExtraBall].into_iter().filter(|&kind| app.shown(kind)).map(|kind| app.world.count(kind)).sum::<usize>(),
+            app.world.entities().filter(|(_, kind)| matches!(kind, Kind::Paddle(_))).count(),
+            bricks
+        )),
+    ];
+    let paragraph = Paragraph::new(text)
+        .block(Block::default().borders(Borders::ALL).title("Debug"))
+        .style(Style::default().fg(Color::Gray));
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+// The message on show in the top right corner, over whatever is there and below the
+// diagnostics when they are open
+fn toast_ui(f: &mut Frame, app: &App) {
+    let Some(text) = app.toasts.current() else {
+        return;
+    };
+    let size = f.area();
+    let top = if app.debug.visible { size.y + 11 } else { size.y + 1 };
+    let width = (text.chars().count() as u16 + 4).min(size.width - 2);
+    let area = Rect::new(size.right() - width - 1, top, width, 3).intersection(size);
+    let paragraph = Paragraph::new(text)
+        .block(Block::default().borders(Borders::ALL))
+        .style(Style::default().fg(Color::Yellow))
+        .alignment(Alignment::Center);
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+// The court's keys as they are actually mapped, turned, mirrored and bound
+pub(crate) fn court_help(app: &App) -> Vec<String> {
+    let locale = &app.locale;
+    let keys = |way| input::keys_for(app, way);
+    let mut lines = vec![locale.format("help.move", &[&keys(KeyCode::Left), &keys(KeyCode::Right)])];
+    if app.vertical {
+        lines.push(locale.format("help.vertical", &[&keys(KeyCode::Up), &keys(KeyCode::Down)]));
+    }
+    if app.practice {
+        lines.push(locale.text("help.practice"));
+    }
+    if app.editor.is_some() {
+        lines.push(locale.text("help.editor"));
+    }
+    lines.push(locale.text("help.keys"));
+    lines.push(locale.text("help.speed"));
+    lines.push(locale.text("help.debug"));
+    lines
+}
+
+// Keys for the current screen, then the rules and what it takes to win
+fn help_ui(f: &mut Frame, app: &App) {
+    let locale = &app.locale;
+    let mut lines = vec![String::new()];
+    lines.extend(app.screen().help(app));
+    lines.push(String::new());
+    lines.push(locale.text(if app.rally { "help.rules_rally" } else { "help.rules" }));
+    lines.push(match &app.series {
+        _ if app.practice => locale.text("help.win_practice"),
+        Some(series) => locale.format("help.win_series", &[&series.best_of, &app.win_score]),
+        None => locale.format("help.win", &[&app.win_score]),
+    });
+    lines.push(String::new());
+    lines.push(locale.text("help.close"));
+
+    let size = f.area();
+    let width = lines.iter().map(|line| line.chars().count()).max().unwrap_or(0) as u16 + 4;
+    let height = lines.len() as u16 + 2;
+    let area = Rect::new(
+        size.x + size.width.saturating_sub(width) / 2,
+        size.y + size.height.saturating_sub(height) / 2,
+        width.min(size.width),
+        height.min(size.height),
+    );
+    let paragraph = Paragraph::new(lines.into_iter().map(Line::from).collect::<Vec<_>>())
+        .block(Block::default().borders(Borders::ALL).title(locale.text("help")))
+        .alignment(Alignment::Center);
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+pub(crate) fn settings_ui(f: &mut Frame, app: &App) {
+    let locale = &app.locale;
+    let settings = &app.settings;
+    let key = |key: Option<char>| match key {
+        Some(c) => format!("{}", c),
+        None => locale.text("settings.unbound"),
+    };
+    let on_off = |on: bool| locale.text(if on { "settings.on" } else { "settings.off" });
+    let mut lines = vec![Line::from("")];
+    for (i, row) in ROWS.iter().enumerate() {
+        let (name, value) = match row {
+            Row::Palette => ("settings.palette", settings.palette.clone()),
+            Row::Volume => ("settings.volume", format!("{}%", settings.volume)),
+            Row::Difficulty => (
+                "settings.difficulty",
+                locale.text(match settings.difficulty {
+                    Difficulty::Easy => "difficulty.easy",
+                    Difficulty::Normal => "difficulty.normal",
+                    Difficulty::Hard => "difficulty.hard",
+                }),
+            ),
+            Row::Marker => (
+                "settings.marker",
+                locale.text(match settings.marker {
+                    MarkerStyle::Braille => "marker.braille",
+                    MarkerStyle::Block => "marker.block",
+                    MarkerStyle::Dot => "marker.dot",
+                }),
+            ),
+            Row::HalfBlocks => ("settings.half_blocks", on_off(settings.half_blocks)),
+            Row::Background => (
+                "settings.background",
+                locale.text(match settings.background {
+                    Background::Off => "settings.off",
+                    Background::Stars => "background.stars",
+                    Background::Grid => "background.grid",
+                }),
+            ),
+            Row::PaddleSpeed => ("settings.paddle_speed", format!("{}", settings.paddle_speed)),
+            Row::Acceleration => (
+                "settings.acceleration",
+                locale.text(match settings.acceleration {
+                    Acceleration::Off => "settings.off",
+                    Acceleration::Smooth => "acceleration.smooth",
+                    Acceleration::Heavy => "acceleration.heavy",
+                }),
+            ),
+            Row::LeftKey => ("settings.left", key(settings.left)),
+            Row::RightKey => ("settings.right", key(settings.right)),
+            Row::Mirror => ("settings.mirror", on_off(settings.modifiers.mirror)),
+            Row::Blink => ("settings.blink", on_off(settings.modifiers.blink)),
+            Row::Walls => ("settings.walls", on_off(settings.modifiers.walls)),
+            Row::Shadow => ("settings.shadow", on_off(settings.modifiers.shadow)),
+        };
+        let selected = i == app.settings_menu.selected;
+        let value = if selected && app.settings_menu.binding { locale.text("settings.press") } else { value };
+        let style = if selected {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(Span::styled(format!("{:<14}< {} >", locale.text(name), value), style)));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(locale.text("settings.keys")));
+
+    let court = screen_chunks(f.area(), app.zen)[0];
+    let width = 50.min(court.width);
+    let height = (lines.len() as u16 + 2).min(court.height);
+    let area = Rect::new(court.x + court.width.saturating_sub(width) / 2, court.y, width, height);
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(locale.text("settings")))
+        .alignment(Alignment::Center);
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+// The win banner hops and runs through the colours until the results come up, then
+// settles. Returns the hop and the colour
+fn celebration(app: &App) -> (f64, Color) {
+    if !app.win {
+        return (0.0, app.palette.lose);
+    }
+    if app.reduced_motion || app.monochrome || app.celebration >= CELEBRATION_TICKS {
+        return (0.0, app.palette.win);
+    }
+    (animation::bounce(app.celebration, 0), animation::cycle(app.celebration, 0))
+}
+
+// How the game went and what to do next, over the court once a win has been celebrated
+pub(crate) fn results_ui(f: &mut Frame, app: &App, results: &Results) {
+    let locale = &app.locale;
+    let summary = app.summary().map(|summary| summary.lines(locale)).unwrap_or_default();
+    let mut lines: Vec<Line> = summary.into_iter().map(Line::from).collect();
+    lines.push(Line::from(""));
+    for (i, choice) in results.choices().iter().enumerate() {
+        let style = if i == results.selected {
+            Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(Span::styled(locale.text(choice.key()), style)));
+    }
+
+    let court = screen_chunks(f.area(), app.zen)[0];
+    let width = 50.min(court.width);
+    // long summary lines wrap, so each takes as many rows as it needs
+    let inner = width.saturating_sub(2).max(1) as usize;
+    let rows: usize = lines.iter().map(|line| line.width().max(1).div_ceil(inner)).sum();
+    let height = (rows as u16 + 2).min(court.height);
+    let area = Rect::new(
+        court.x + court.width.saturating_sub(width) / 2,
+        court.y + court.height.saturating_sub(height) / 2,
+        width,
+        height,
+    );
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(locale.text("results")))
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true });
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+pub(crate) fn paused_ui(f: &mut Frame, app: &App) {
+    let court = screen_chunks(f.area(), app.zen)[0];
+    let (title, keys) = if app.quitting {
+        (app.locale.text("quit"), app.locale.text("quit.keys"))
+    } else if app.idle {
+        (app.locale.text("idle"), app.locale.text("idle.keys"))
+    } else {
+        (app.locale.text("paused"), app.locale.text("paused.keys"))
+    };
+    let width = keys.chars().count() as u16 + 2;
+    let area = Rect::new(
+        court.x + court.width.saturating_sub(width) / 2,
+        court.y + court.height.saturating_sub(5) / 2,
+        width.min(court.width),
+        5.min(court.height),
+    );
+    let paragraph = Paragraph::new(format!("\n{}", keys))
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .alignment(Alignment::Center);
+    f.render_widget(Clear, area);
+    f.render_widget(paragraph, area);
+}
+
+pub(crate) fn editor_ui(f: &mut Frame, app: &App) {
+    let editor = app.editor.as_ref().unwrap();
+    let chunks = screen_chunks(f.area(), false);
+
+    let canvas = Canvas::default()
+        .block(Block::default().borders(Borders::ALL).title(app.locale.text("editor.title")))
+        .marker(marker(app))
+        .paint(|ctx| {
+            for brick in &editor.level.bricks {
+                let rect = Rectangle { color: app.palette.brick(brick.kind, brick.row), ..brick.rect.clone() };
+                if brick.kind == BrickKind::Obstacle && app.palette.solid_obstacles {
+                    ctx.draw(&Solid(rect));
+                } else {
+                    ctx.draw(&rect);
+                }
+            }
+            ctx.draw(&Rectangle { color: app.palette.paddle, ..app.world[app.board].clone() });
+            ctx.draw(&Rectangle { color: app.palette.paddle, ..app.world[app.cpu].clone() });
+            ctx.layer();
+            if app.monochrome {
+                ctx.draw(&Solid(editor.cursor_rect())); //the yellow would be lost
+            } else {
+                ctx.draw(&editor.cursor_rect());
+            }
+        })
+        .x_bounds(Court::FULL.x_bounds())
+        .y_bounds(Court::FULL.y_bounds());
+    f.render_widget(canvas, chunks[0]);
+
+    let help = format!(
+        "{}\n\n{}\n{}",
+        editor.status,
+        app.locale.text("editor.keys"),
+        app.locale.text("editor.return")
+    );
+    let paragraph = Paragraph::new(help)
+        .block(Block::default().title(app.locale.text("editor")).borders(Borders::ALL));
+    f.render_widget(paragraph, chunks[1]);
+}
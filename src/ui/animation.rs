@@ -0,0 +1,87 @@
+use std::f64::consts::PI;
+
+use ratatui::{style::Color, widgets::canvas::Rectangle};
+
+use crate::events::{GameEvent, Paddle};
+
+const LOGO_COLORS: [Color; 6] = [
+    Color::LightRed,
+    Color::LightYellow,
+    Color::LightGreen,
+    Color::LightCyan,
+    Color::LightBlue,
+    Color::LightMagenta,
+];
+
+// Steps through the palette every eight frames, `offset` staggers neighbouring letters
+pub fn cycle(frame: u64, offset: usize) -> Color {
+    LOGO_COLORS[(frame / 8 + offset as u64) as usize % LOGO_COLORS.len()]
+}
+
+// Height of a hop between 0 and 1, letters take turns one after another
+pub fn bounce(frame: u64, offset: usize) -> f64 {
+    let phase = (frame as f64 / 6.0 - offset as f64 * 0.8).sin();
+    phase.max(0.0)
+}
+
+const SQUASH_TICKS: u64 = 8; //how long a squashed paddle or ball takes to spring back
+const PADDLE_SQUASH: (f64, f64) = (0.25, -0.4); //wider and flatter at the moment of a hit
+const BALL_SQUASH: (f64, f64) = (-0.35, 0.35); //narrower and taller off a side wall
+
+// The last ticks the ball struck each paddle and a side wall, read off the event bus so
+// the court can squash whatever it hit for a few ticks
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Impacts {
+    player: Option<u64>,
+    cpu: Option<u64>,
+    wall: Option<u64>,
+}
+
+impl Impacts {
+    pub fn feed(&mut self, events: &[GameEvent], tick: u64) {
+        for event in events {
+            match event {
+                GameEvent::BallHitPaddle { paddle: Paddle::Player, .. } => self.player = Some(tick),
+                GameEvent::BallHitPaddle { paddle: Paddle::Cpu, .. } => self.cpu = Some(tick),
+                GameEvent::BallHitWall => self.wall = Some(tick),
+                _ => {}
+            }
+        }
+    }
+
+    // Horizontal and vertical scale of a paddle at `tick`, 1.0 each once it has settled
+    pub fn paddle(&self, paddle: Paddle, tick: u64) -> (f64, f64) {
+        let at = match paddle {
+            Paddle::Player => self.player,
+            Paddle::Cpu => self.cpu,
+        };
+        scale(PADDLE_SQUASH, at, tick)
+    }
+
+    pub fn ball(&self, tick: u64) -> (f64, f64) {
+        scale(BALL_SQUASH, self.wall, tick)
+    }
+}
+
+// Squashed all the way at the hit, then overshooting a little into a stretch before it
+// settles
+fn scale(squash: (f64, f64), at: Option<u64>, tick: u64) -> (f64, f64) {
+    let Some(age) = at.map(|at| tick.saturating_sub(at)).filter(|age| *age < SQUASH_TICKS) else {
+        return (1.0, 1.0);
+    };
+    let t = age as f64 / SQUASH_TICKS as f64;
+    let k = (1.0 - t) * (t * PI * 1.5).cos();
+    (1.0 + squash.0 * k, 1.0 + squash.1 * k)
+}
+
+// `rect` scaled about its middle
+pub fn scaled(rect: &Rectangle, (sx, sy): (f64, f64)) -> Rectangle {
+    let (width, height) = (rect.width * sx, rect.height * sy);
+    Rectangle {
+        x: rect.x + (rect.width - width) / 2.0,
+        y: rect.y + (rect.height - height) / 2.0,
+        width,
+        height,
+        color: rect.color,
+    }
+}
@@ -0,0 +1,110 @@
+use ratatui::{
+    style::Color,
+    widgets::canvas::{Line, Painter, Points, Rectangle, Shape},
+};
+
+use crate::{
+    app::Orientation,
+    court::Court,
+    level::ZoneKind,
+};
+
+const STARS: u64 = 48;
+const GRID_STEP: f64 = 15.0; //court units between grid lines
+
+// Scattered stars drifting from the cpu's end towards the player's, the nearer ones twice
+// as fast and a little brighter. An offset of 0 holds them still
+pub struct Starfield {
+    pub offset: f64, //court units the far stars have moved
+    pub orientation: Orientation,
+}
+
+impl Shape for Starfield {
+    fn draw(&self, painter: &mut Painter) {
+        let court = Court::FULL;
+        let (left, bottom) = (court.left(), court.bottom());
+        let (width, height) = (court.width(), court.height());
+        let (mut far, mut near) = (Vec::new(), Vec::new());
+        for star in 0..STARS {
+            // the same spots every frame without keeping any state, from a multiplicative hash
+            let hash = (star + 1).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+            let x = (hash >> 40) as f64 / (1u64 << 24) as f64 * width;
+            let y = (hash >> 16 & 0xFF_FFFF) as f64 / (1u64 << 24) as f64 * height;
+            let is_near = star % 3 == 0;
+            let speed = if is_near { 2.0 } else { 1.0 };
+            let y = (y - self.offset * speed).rem_euclid(height);
+            let point = match self.orientation {
+                Orientation::Upright => (left + x, bottom + y),
+                Orientation::Sideways => (bottom + y, left + x),
+            };
+            if is_near {
+                near.push(point);
+            } else {
+                far.push(point);
+            }
+        }
+        Points { coords: &far, color: Color::DarkGray }.draw(painter);
+        Points { coords: &near, color: Color::Gray }.draw(painter);
+    }
+}
+
+// Faint lines across the court at even steps
+pub struct Grid {
+    pub orientation: Orientation,
+}
+
+impl Shape for Grid {
+    fn draw(&self, painter: &mut Painter) {
+        let [left, right] = Court::FULL.x_bounds();
+        let [bottom, top] = Court::FULL.y_bounds();
+        let mut lines = Vec::new();
+        let mut x = left + GRID_STEP;
+        while x < right {
+            lines.push((x, bottom, x, top));
+            x += GRID_STEP;
+        }
+        let mut y = bottom + GRID_STEP;
+        while y < top {
+            lines.push((left, y, right, y));
+            y += GRID_STEP;
+        }
+        for (x1, y1, x2, y2) in lines {
+            let line = match self.orientation {
+                Orientation::Upright => Line { x1, y1, x2, y2, color: Color::DarkGray },
+                Orientation::Sideways => Line { x1: y1, y1: x1, x2: y2, y2: x2, color: Color::DarkGray },
+            };
+            line.draw(painter);
+        }
+    }
+}
+
+// A court zone shaded in with a sparse pattern of dots, diagonals for a boost strip, a
+// scatter for a slow zone and rows for a lift pocket, so they differ without colour
+pub struct Shade {
+    pub rect: Rectangle,
+    pub kind: ZoneKind,
+}
+
+impl Shape for Shade {
+    fn draw(&self, painter: &mut Painter) {
+        let rect = &self.rect;
+        let (Some(top_left), Some(bottom_right)) = (
+            painter.get_point(rect.x, rect.y + rect.height),
+            painter.get_point(rect.x + rect.width, rect.y),
+        ) else {
+            return;
+        };
+        for x in top_left.0..=bottom_right.0 {
+            for y in top_left.1..=bottom_right.1 {
+                let set = match self.kind {
+                    ZoneKind::Boost => (x + y) % 4 == 0,
+                    ZoneKind::Slow => x % 4 == 0 && y % 4 == 0,
+                    ZoneKind::Lift => y % 4 == 0 && x % 2 == 0,
+                };
+                if set {
+                    painter.paint(x, y, rect.color);
+                }
+            }
+        }
+    }
+}
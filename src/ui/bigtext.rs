@@ -0,0 +1,136 @@
+use ratatui::{
+    style::Color,
+    symbols::Marker,
+    layout::Rect,
+    widgets::canvas::{Painter, Shape},
+};
+
+const GLYPH_WIDTH: usize = 3;
+const GLYPH_HEIGHT: usize = 5;
+
+// Every glyph is three pixels wide and five high, one row per entry with the leftmost
+// pixel in the highest bit
+fn glyph(c: char) -> [u8; GLYPH_HEIGHT] {
+    match c.to_ascii_uppercase() {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b011, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '!' => [0b010, 0b010, 0b010, 0b000, 0b010],
+        _ => [0; GLYPH_HEIGHT],
+    }
+}
+
+// Text width in pixels, glyphs are one pixel apart
+fn columns(text: &str) -> usize {
+    (text.chars().count() * (GLYPH_WIDTH + 1)).saturating_sub(1)
+}
+
+// Large text drawn with filled pixels, (x, y) is the bottom left corner in canvas units
+pub struct BigText<'a> {
+    pub text: &'a str,
+    pub x: f64,
+    pub y: f64,
+    pub pixel: (f64, f64),
+    pub color: Color,
+}
+
+impl<'a> BigText<'a> {
+    // Scales the text to take up `fill` of the bounds in both directions and centers it
+    pub fn fit(text: &'a str, x_bounds: [f64; 2], y_bounds: [f64; 2], fill: f64, color: Color) -> BigText<'a> {
+        let width = x_bounds[1] - x_bounds[0];
+        let height = y_bounds[1] - y_bounds[0];
+        let pixel = (
+            width * fill / columns(text).max(1) as f64,
+            height * fill / GLYPH_HEIGHT as f64,
+        );
+        BigText {
+            text,
+            x: x_bounds[0] + (width - pixel.0 * columns(text) as f64) / 2.0,
+            y: y_bounds[0] + (height - pixel.1 * GLYPH_HEIGHT as f64) / 2.0,
+            pixel,
+            color,
+        }
+    }
+}
+
+// Whether the canvas has at least one dot per pixel of text filling `fill` of it
+pub fn legible(text: &str, area: Rect, marker: Marker, fill: f64) -> bool {
+    let (dots_x, dots_y) = match marker {
+        Marker::Braille => (2.0, 4.0),
+        _ => (1.0, 1.0),
+    };
+    area.width as f64 * dots_x * fill >= columns(text) as f64 && area.height as f64 * dots_y * fill >= GLYPH_HEIGHT as f64
+}
+
+impl<'a> Shape for BigText<'a> {
+    fn draw(&self, painter: &mut Painter) {
+        for (i, c) in self.text.chars().enumerate() {
+            let left = self.x + (i * (GLYPH_WIDTH + 1)) as f64 * self.pixel.0;
+            for (row, bits) in glyph(c).iter().enumerate() {
+                for col in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - col)) == 0 {
+                        continue;
+                    }
+                    let x = left + col as f64 * self.pixel.0;
+                    let y = self.y + (GLYPH_HEIGHT - 1 - row) as f64 * self.pixel.1;
+                    fill(painter, x, y, self.pixel, self.color);
+                }
+            }
+        }
+    }
+}
+
+// Paints every dot inside one pixel, the far edges are pulled in a little so neighbouring
+// pixels don't bleed into each other. Returns false without painting when part of the
+// pixel is off the canvas
+pub(super) fn fill(painter: &mut Painter, x: f64, y: f64, pixel: (f64, f64), color: Color) -> bool {
+    let inset = (pixel.0 * 0.01, pixel.1 * 0.01);
+    let (Some(top_left), Some(bottom_right)) = (
+        painter.get_point(x, y + pixel.1 - inset.1),
+        painter.get_point(x + pixel.0 - inset.0, y),
+    ) else {
+        return false;
+    };
+    for gx in top_left.0..=bottom_right.0 {
+        for gy in top_left.1..=bottom_right.1 {
+            painter.paint(gx, gy, color);
+        }
+    }
+    true
+}
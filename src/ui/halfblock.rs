@@ -0,0 +1,63 @@
+use ratatui::{buffer::Buffer, layout::Rect, widgets::{canvas::Rectangle, Widget}};
+
+// Quarter cell blocks by which quarters are set, top left in the lowest bit, then top
+// right, bottom left and bottom right
+const QUARTERS: [&str; 16] = [
+    " ", "▘", "▝", "▀", "▖", "▌", "▞", "▛", "▗", "▚", "▐", "▜", "▄", "▙", "▟", "█",
+];
+
+// Filled rectangles in canvas units painted straight into the cells, with half blocks on
+// their edges, so they move half a cell at a time where the block marker jumps whole cells.
+// Drawn over the canvas, inside its borders
+pub struct HalfBlocks<'a> {
+    pub rects: &'a [Rectangle],
+    pub x_bounds: [f64; 2],
+    pub y_bounds: [f64; 2],
+}
+
+impl Widget for HalfBlocks<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let (columns, rows) = (area.width as f64 * 2.0, area.height as f64 * 2.0);
+        let [left, right] = self.x_bounds;
+        let [bottom, top] = self.y_bounds;
+        // half cells across from the left and down from the top, at least one of each, none
+        // for a rectangle entirely off the court
+        let span = |from: f64, to: f64, halves: f64| {
+            if to <= 0.0 || from >= halves {
+                return None;
+            }
+            let start = from.round().clamp(0.0, halves - 1.0);
+            let end = to.round().clamp(start + 1.0, halves);
+            Some((start as u16, end as u16))
+        };
+        for rect in self.rects {
+            let x = span(
+                (rect.x - left) / (right - left) * columns,
+                (rect.x + rect.width - left) / (right - left) * columns,
+                columns,
+            );
+            let y = span(
+                (top - rect.y - rect.height) / (top - bottom) * rows,
+                (top - rect.y) / (top - bottom) * rows,
+                rows,
+            );
+            let (Some((x0, x1)), Some((y0, y1))) = (x, y) else {
+                continue;
+            };
+            for row in y0 / 2..y1.div_ceil(2) {
+                for column in x0 / 2..x1.div_ceil(2) {
+                    let set = |x: u16, y: u16| (x0..x1).contains(&x) && (y0..y1).contains(&y);
+                    let quarters = set(column * 2, row * 2) as usize
+                        | (set(column * 2 + 1, row * 2) as usize) << 1
+                        | (set(column * 2, row * 2 + 1) as usize) << 2
+                        | (set(column * 2 + 1, row * 2 + 1) as usize) << 3;
+                    if quarters != 0 {
+                        buf[(area.x + column, area.y + row)]
+                            .set_symbol(QUARTERS[quarters])
+                            .set_fg(rect.color);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use ratatui::widgets::canvas::{Painter, Rectangle, Shape};
+
+// The ball as a disc filling its rectangle, in canvas units like everything else
+pub struct Disc {
+    pub rect: Rectangle,
+    pub filled: bool,
+}
+
+impl Shape for Disc {
+    fn draw(&self, painter: &mut Painter) {
+        let rect = &self.rect;
+        let (cx, cy) = (rect.x + rect.width / 2.0, rect.y + rect.height / 2.0);
+        let inside = |x: f64, y: f64| {
+            let dx = (x - cx) / (rect.width / 2.0);
+            let dy = (y - cy) / (rect.height / 2.0);
+            dx * dx + dy * dy <= 1.0
+        };
+        paint(painter, rect, self.filled, inside);
+    }
+}
+
+// A paddle with round ends, a half circle across its short side at either end
+pub struct Capsule {
+    pub rect: Rectangle,
+    pub filled: bool,
+}
+
+impl Shape for Capsule {
+    fn draw(&self, painter: &mut Painter) {
+        let rect = &self.rect;
+        let radius = rect.width.min(rect.height) / 2.0;
+        let (cx, cy) = (rect.x + rect.width / 2.0, rect.y + rect.height / 2.0);
+        // the segment between the centres of the two ends
+        let (half_x, half_y) = (rect.width / 2.0 - radius, rect.height / 2.0 - radius);
+        let inside = |x: f64, y: f64| {
+            let dx = ((x - cx).abs() - half_x).max(0.0);
+            let dy = ((y - cy).abs() - half_y).max(0.0);
+            dx * dx + dy * dy <= radius * radius
+        };
+        paint(painter, rect, self.filled, inside);
+    }
+}
+
+// Paints the dots inside the rectangle that are inside the shape too, or only those on its
+// edge when not filled. Something half off the canvas is drawn as its outline, and one too
+// small to cover a dot still gets the one in its middle
+fn paint(painter: &mut Painter, rect: &Rectangle, filled: bool, inside: impl Fn(f64, f64) -> bool) {
+    let (Some(top_left), Some(bottom_right)) = (
+        painter.get_point(rect.x, rect.y + rect.height),
+        painter.get_point(rect.x + rect.width, rect.y),
+    ) else {
+        rect.draw(painter);
+        return;
+    };
+    let columns = bottom_right.0 - top_left.0 + 1;
+    let rows = bottom_right.1 - top_left.1 + 1;
+    // the middle of a dot in canvas units, rows count down from the top
+    let at = |column: isize, row: isize| {
+        (
+            rect.x + (column as f64 + 0.5) / columns as f64 * rect.width,
+            rect.y + rect.height - (row as f64 + 0.5) / rows as f64 * rect.height,
+        )
+    };
+    let set = |column: isize, row: isize| {
+        let (x, y) = at(column, row);
+        inside(x, y)
+    };
+    let mut painted = false;
+    for row in 0..rows as isize {
+        for column in 0..columns as isize {
+            if !set(column, row) {
+                continue;
+            }
+            let edge = !set(column - 1, row) || !set(column + 1, row) || !set(column, row - 1) || !set(column, row + 1);
+            if filled || edge {
+                painter.paint(top_left.0 + column as usize, top_left.1 + row as usize, rect.color);
+                painted = true;
+            }
+        }
+    }
+    if !painted {
+        painter.paint(top_left.0 + columns / 2, top_left.1 + rows / 2, rect.color);
+    }
+}
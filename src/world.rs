@@ -0,0 +1,222 @@
+use std::ops::{Index, IndexMut};
+
+use serde::{Deserialize, Serialize};
+use ratatui::widgets::canvas::Rectangle;
+
+use crate::{court::Court, events::Paddle, level::BrickKind};
+
+// What an entity is, for the systems that treat kinds apart and for drawing it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Kind {
+    Ball,
+    Shadow, //the shadow modifier's decoy, mirroring the ball across the court
+    Paddle(Paddle),
+    Brick(BrickKind), //of the stage being played, laid out from its level
+    Boss, //sweeps from wall to wall on a boss stage
+    ExtraBall, //dropped by a boss, falls towards the player
+    Ring, //target practice, the ball scores going up through one
+}
+
+impl Kind {
+    // The ones that come and go during a game, as opposed to the ball and paddles that
+    // are always there, the shadow that is worked out from the ball and the bricks that
+    // come with the level
+    pub fn passing(self) -> bool {
+        matches!(self, Kind::Boss | Kind::ExtraBall | Kind::Ring)
+    }
+}
+
+// Court units a tick along each axis, negative is left or down
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Velocity {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Velocity {
+    // Whether it goes right and whether up. The sign of a zero speed still tells, so an
+    // axis that stands still keeps the way it went
+    pub fn heading(self) -> (bool, bool) {
+        (self.x.is_sign_positive(), self.y.is_sign_positive())
+    }
+
+    // The same speeds turned right or left and up or down, None leaves that axis be
+    pub fn turned(self, right: Option<bool>, up: Option<bool>) -> Velocity {
+        let turn = |speed: f64, forward: Option<bool>| match forward {
+            Some(forward) => speed.abs().copysign(if forward { 1.0 } else { -1.0 }),
+            None => speed,
+        };
+        Velocity { x: turn(self.x, right), y: turn(self.y, up) }
+    }
+}
+
+// A handle on one entity, good until it is despawned
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Entity(usize);
+
+// Everything on the court that moves or comes and goes, the ball, paddles and bricks
+// included, each an entity whose components sit in the same slot of every list rather than
+// fields of their own on App. A slot is free while it has no kind, and the lowest free one
+// is handed out again by the next spawn, so entities spawned one after another keep their
+// order
+#[derive(Clone, Default)]
+pub struct World {
+    kinds: Vec<Option<Kind>>,
+    bodies: Vec<Rectangle>,
+    velocities: Vec<Option<Velocity>>,
+    expiries: Vec<Option<u64>>, //the tick an entity goes at, for the ones that don't stay
+    free: Vec<usize>, //highest first
+}
+
+impl World {
+    pub fn spawn(&mut self, kind: Kind, body: Rectangle, velocity: Option<Velocity>) -> Entity {
+        match self.free.pop() {
+            Some(slot) => {
+                self.kinds[slot] = Some(kind);
+                self.bodies[slot] = body;
+                self.velocities[slot] = velocity;
+                self.expiries[slot] = None;
+                Entity(slot)
+            }
+            None => {
+                self.kinds.push(Some(kind));
+                self.bodies.push(body);
+                self.velocities.push(velocity);
+                self.expiries.push(None);
+                Entity(self.kinds.len() - 1)
+            }
+        }
+    }
+
+    // Has the lifetime system take the entity out once the clock reaches `tick`
+    pub fn expire_at(&mut self, entity: Entity, tick: u64) {
+        self.expiries[entity.0] = Some(tick);
+    }
+
+    pub fn expiry(&self, entity: Entity) -> Option<u64> {
+        self.expiries[entity.0]
+    }
+
+    pub fn velocity(&self, entity: Entity) -> Option<Velocity> {
+        self.velocities[entity.0]
+    }
+
+    pub fn set_velocity(&mut self, entity: Entity, velocity: Velocity) {
+        self.velocities[entity.0] = Some(velocity);
+    }
+
+    pub fn despawn(&mut self, Entity(slot): Entity) {
+        self.kinds[slot] = None;
+        self.velocities[slot] = None;
+        self.expiries[slot] = None;
+        let at = self.free.partition_point(|&free| free > slot);
+        self.free.insert(at, slot);
+    }
+
+    pub fn despawn_all(&mut self, kind: Kind) {
+        for (slot, _) in self.live().filter(|&(_, of)| of == kind).collect::<Vec<_>>() {
+            self.despawn(Entity(slot));
+        }
+    }
+
+    // Every entity that comes and goes taken out, the ball and paddles stay
+    pub fn clear(&mut self) {
+        for (slot, _) in self.live().filter(|(_, kind)| kind.passing()).collect::<Vec<_>>() {
+            self.despawn(Entity(slot));
+        }
+    }
+
+    fn live(&self) -> impl Iterator<Item = (usize, Kind)> + '_ {
+        self.kinds.iter().enumerate().filter_map(|(slot, kind)| kind.map(|kind| (slot, kind)))
+    }
+
+    pub fn entities(&self) -> impl Iterator<Item = (Entity, Kind)> + '_ {
+        self.live().map(|(slot, kind)| (Entity(slot), kind))
+    }
+
+    pub fn of(&self, kind: Kind) -> impl Iterator<Item = &Rectangle> + '_ {
+        self.live().filter(move |&(_, of)| of == kind).map(|(slot, _)| &self.bodies[slot])
+    }
+
+    pub fn count(&self, kind: Kind) -> usize {
+        self.of(kind).count()
+    }
+
+    // Moves everything with a velocity `dt` ticks along, but never more than `max_step` along
+    // either axis, so however fast the clock runs nothing jumps a brick or a paddle. `pace`
+    // gives how much faster than its own speed an entity travels where it is and how far it
+    // is lifted up the court on top, for the level's zones
+    pub fn movement(&mut self, dt: f64, max_step: f64, pace: impl Fn(&Rectangle) -> (f64, f64)) {
+        let step = |distance: f64| distance.clamp(-max_step, max_step);
+        for (slot, _) in self.live().collect::<Vec<_>>() {
+            let Some(velocity) = self.velocities[slot] else {
+                continue;
+            };
+            let body = &mut self.bodies[slot];
+            let (pace, lift) = pace(body);
+            body.x += step(velocity.x * dt * pace);
+            body.y += step(velocity.y * dt * pace + lift * dt);
+        }
+    }
+
+    // Turns every entity of `kind` back off the side walls it has reached, from just inside them
+    pub fn bounce_off_walls(&mut self, kind: Kind, court: Court) {
+        let (left, right) = (court.left(), court.right());
+        for (slot, _) in self.live().filter(|&(_, of)| of == kind).collect::<Vec<_>>() {
+            let (Some(velocity), body) = (&mut self.velocities[slot], &mut self.bodies[slot]) else {
+                continue;
+            };
+            if body.x + body.width >= right {
+                body.x = (right - body.width).max(left);
+                velocity.x = -velocity.x.abs();
+            } else if body.x <= left {
+                body.x = left;
+                velocity.x = velocity.x.abs();
+            }
+        }
+    }
+
+    // Takes out every entity of `kind` that `contact` finds touching something, and hands
+    // back what it found for each, in the order of their slots
+    pub fn collide<T>(&mut self, kind: Kind, mut contact: impl FnMut(&Rectangle) -> Option<T>) -> Vec<T> {
+        let mut found = Vec::new();
+        for (slot, _) in self.live().filter(|&(_, of)| of == kind).collect::<Vec<_>>() {
+            if let Some(touched) = contact(&self.bodies[slot]) {
+                self.despawn(Entity(slot));
+                found.push(touched);
+            }
+        }
+        found
+    }
+
+    // The lifetime system, takes out whatever has had its time by `tick`
+    pub fn expire(&mut self, tick: u64) {
+        let done: Vec<_> = self.live().filter(|&(slot, _)| self.expiries[slot].is_some_and(|at| at <= tick)).collect();
+        for (slot, _) in done {
+            self.despawn(Entity(slot));
+        }
+    }
+
+    // Puts every shadow where `of` would be with the court's side walls swapped
+    pub fn mirror(&mut self, of: Entity, court: Court) {
+        let ball = self.bodies[of.0].clone();
+        let x = court.left() + court.right() - ball.x - ball.width;
+        for (slot, _) in self.live().filter(|&(_, kind)| kind == Kind::Shadow).collect::<Vec<_>>() {
+            self.bodies[slot] = Rectangle { x, ..ball.clone() };
+        }
+    }
+}
+
+impl Index<Entity> for World {
+    type Output = Rectangle;
+
+    fn index(&self, entity: Entity) -> &Rectangle {
+        &self.bodies[entity.0]
+    }
+}
+
+impl IndexMut<Entity> for World {
+    fn index_mut(&mut self, entity: Entity) -> &mut Rectangle {
+        &mut self.bodies[entity.0]
+    }
+}
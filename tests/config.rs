@@ -0,0 +1,74 @@
+use pong_terminal::Config;
+
+fn parse(line: &str) -> Result<Config, String> {
+    Config::from_args(line.split_whitespace().map(String::from))
+}
+
+#[test]
+fn game_modes_switch_on() {
+    let config = parse("--targets --time-attack --rally --hardcore --practice --shrink --vertical --sideways").unwrap();
+    assert!(config.targets && config.time_attack && config.rally && config.hardcore);
+    assert!(config.practice && config.shrink && config.vertical && config.sideways);
+    assert!(!config.procedural && !config.simulate);
+
+    let config = parse("simulate --games 20 --procedural --seed 5").unwrap();
+    assert!(config.simulate && config.procedural);
+    assert_eq!((config.games, config.seed), (Some(20), Some(5)));
+}
+
+#[test]
+fn numbers_are_read_and_kept_in_range() {
+    let config = parse("--win-score 3 --best-of 5 --speed 1.5 --slow-motion 0.5 --max-speed 6 --paddle-speed 8").unwrap();
+    assert_eq!((config.win_score, config.best_of), (Some(3), Some(5)));
+    assert_eq!((config.speed, config.slow_motion, config.max_speed), (Some(1.5), Some(0.5), Some(6.0)));
+    assert_eq!(config.paddle_speed, Some(8));
+
+    for line in ["--win-score 0", "--speed 4", "--slow-motion 0", "--max-speed 0.5", "--paddle-speed 11", "--games x"] {
+        assert!(parse(line).is_err(), "{} was accepted", line);
+    }
+}
+
+#[test]
+fn sizes_are_width_by_height() {
+    let config = parse("--ball 4x6 --paddle 20x3").unwrap();
+    assert_eq!((config.ball_size, config.paddle_size), (Some((4.0, 6.0)), Some((20.0, 3.0))));
+    assert!(parse("--ball 4").is_err());
+    assert!(parse("--paddle 500x3").is_err());
+}
+
+#[test]
+fn comfort_flags() {
+    let config = parse("--idle-pause 0 --no-confirm-quit --no-focus-pause --reduced-motion --monochrome --max-fps 30").unwrap();
+    assert_eq!((config.idle_pause, config.max_fps), (Some(0), Some(30)));
+    assert!(config.no_confirm_quit && config.no_focus_pause && config.reduced_motion && config.monochrome);
+    assert!(parse("--idle-pause soon").is_err());
+}
+
+#[test]
+fn sound_backends_and_the_audio_offset() {
+    let config = parse("--sound bell --audio-offset 200").unwrap();
+    assert_eq!((config.audio.as_deref(), config.audio_offset), (Some("bell"), Some(200)));
+    assert_eq!(parse("--audio silent").unwrap().audio.as_deref(), Some("silent"));
+    assert!(parse("--sound speaker").is_err());
+    assert!(parse("--audio-offset 201").is_err());
+}
+
+#[test]
+fn names_have_to_be_known() {
+    assert!(parse("--difficulty hard --acceleration heavy --palette tritanopia --modifiers mirror,walls").is_ok());
+    for line in ["--difficulty brutal", "--acceleration jerky", "--palette sepia", "--modifiers gravity", "--profile a/b"] {
+        assert!(parse(line).is_err(), "{} was accepted", line);
+    }
+}
+
+#[test]
+fn output_files_and_an_unknown_option() {
+    let config = parse("--stats-file stats.jsonl --record-cast game.cast --export-telemetry t.csv --quiet --json-summary").unwrap();
+    assert_eq!(config.stats_file.unwrap().to_str(), Some("stats.jsonl"));
+    assert_eq!(config.record_cast.unwrap().to_str(), Some("game.cast"));
+    assert_eq!(config.export_telemetry.unwrap().to_str(), Some("t.csv"));
+    assert!(config.quiet && config.json_summary);
+
+    assert_eq!(parse("--fast").err(), Some(String::from("unknown option '--fast'")));
+    assert!(parse("--stats-file").is_err());
+}
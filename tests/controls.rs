@@ -0,0 +1,143 @@
+use std::collections::VecDeque;
+
+use crossterm::event::KeyCode;
+use pong_terminal::{Config, GameEvent, Input, Simulation};
+
+const SEED: u64 = 7;
+
+// A recording as a replay keeps it, each key stamped with the step it came in on
+fn keys(keys: &[(u64, KeyCode)]) -> VecDeque<(u64, Input)> {
+    keys.iter().map(|&(step, code)| (step, Input::Key(code))).collect()
+}
+
+fn played(recording: &[(u64, KeyCode)], ticks: u64) -> Simulation {
+    let mut sim = Simulation::new(SEED);
+    assert!(!sim.play(&mut keys(recording), ticks), "the recording quit the game");
+    sim
+}
+
+#[test]
+fn opposite_arrows_cancel_out() {
+    // the paddle starts against the left wall
+    let start = Simulation::new(SEED).paddle();
+    assert_eq!(played(&[(5, KeyCode::Left)], 20).paddle(), start);
+    let right = played(&[(5, KeyCode::Right)], 20);
+    assert!(right.paddle() > start);
+    let there_and_back = played(&[(5, KeyCode::Right), (6, KeyCode::Right), (7, KeyCode::Left)], 20);
+    assert_eq!(there_and_back.paddle(), right.paddle());
+}
+
+// The cpu's paddle moves faster with the game, the player's has to keep up
+#[test]
+fn the_paddle_keeps_up_with_the_game_speed() {
+    let moved = |speed: f64| {
+        let mut sim = Simulation::with_config(Config { seed: Some(SEED), speed: Some(speed), ..Config::default() });
+        let start = sim.paddle();
+        sim.press(KeyCode::Right);
+        sim.paddle() - start
+    };
+    assert_eq!(moved(2.0), moved(1.0) * 2.0);
+}
+
+#[test]
+fn the_paddle_stops_flush_against_both_walls() {
+    let mut sim = Simulation::new(SEED);
+    sim.set_court(153, 100); //not a whole number of steps across
+    let court = sim.playground();
+    assert!(!sim.play(&mut keys(&[(0, KeyCode::Right); 40]), 1));
+    assert_eq!(sim.paddle() + 10.0, court.right()); //the paddle is 10 wide
+    assert!(!sim.play(&mut keys(&[(1, KeyCode::Left); 40]), 1));
+    assert_eq!(sim.paddle(), court.left());
+}
+
+#[test]
+fn the_same_recording_plays_the_same_game() {
+    let recording: Vec<_> = (0..60)
+        .map(|n| (n * 17, if n % 3 == 0 { KeyCode::Right } else { KeyCode::Left }))
+        .collect();
+    let (first, second) = (played(&recording, 2000), played(&recording, 2000));
+    assert_eq!(first.ticks(), second.ticks());
+    assert_eq!(first.ball(), second.ball());
+    assert_eq!(first.paddle(), second.paddle());
+    assert_eq!((first.score(), first.cpu_score()), (second.score(), second.cpu_score()));
+}
+
+#[test]
+fn pausing_holds_the_clock() {
+    let paused = played(&[(30, KeyCode::Char('p'))], 200);
+    assert!(paused.paused());
+    assert_eq!(paused.ticks(), 30);
+    assert_eq!(paused.ball(), played(&[], 30).ball());
+
+    // resumed on the same step the game carries on as if it never stopped
+    let resumed = played(&[(30, KeyCode::Char('p')), (30, KeyCode::Char('p'))], 200);
+    assert!(!resumed.paused());
+    assert_eq!(resumed.ticks(), 200);
+    assert_eq!(resumed.ball(), played(&[], 200).ball());
+}
+
+#[test]
+fn quitting_asks_first() {
+    let stayed = played(&[(10, KeyCode::Char('q')), (10, KeyCode::Char('n'))], 50);
+    assert!(!stayed.paused());
+    assert_eq!(stayed.ticks(), 50);
+
+    let mut sim = Simulation::new(SEED);
+    let recording = [(10, KeyCode::Char('q')), (10, KeyCode::Char('y'))];
+    assert!(sim.play(&mut keys(&recording), 50));
+    assert_eq!(sim.ticks(), 10);
+}
+
+#[test]
+fn quitting_from_an_instant_replay_asks_first() {
+    let mut sim = Simulation::new(SEED);
+    while sim.events().iter().all(|event| !matches!(event, GameEvent::PointScored | GameEvent::LifeLost)) {
+        sim.tick();
+    }
+    sim.press(KeyCode::Char('i'));
+    assert!(!sim.press(KeyCode::Char('q')));
+    assert!(sim.paused());
+    assert!(sim.press(KeyCode::Char('y')));
+}
+
+#[test]
+fn escape_leaves_for_the_menu() {
+    let sim = played(&[(10, KeyCode::Esc)], 50);
+    assert!(sim.in_menu());
+    assert_eq!(sim.ticks(), 10);
+}
+
+#[test]
+fn restart_clears_the_score() {
+    let mut sim = Simulation::new(SEED);
+    sim.set_score(4);
+    assert!(!sim.play(&mut keys(&[(10, KeyCode::Char('r'))]), 11));
+    assert_eq!((sim.score(), sim.cpu_score()), (0, 0));
+}
+
+#[test]
+fn the_paddle_holds_still_for_the_celebration() {
+    let mut sim = Simulation::new(SEED);
+    sim.set_score(10);
+    sim.tick();
+    assert!(sim.game_over() && !sim.results_shown());
+    let paddle = sim.paddle();
+    sim.press(KeyCode::Right);
+    assert_eq!(sim.paddle(), paddle);
+}
+
+#[test]
+fn enter_on_the_results_starts_the_next_game() {
+    let mut sim = Simulation::new(SEED);
+    sim.set_score(10);
+    // the clock stops for the celebration, the results come up while the step stays put
+    assert!(!sim.play(&mut keys(&[]), 200));
+    assert!(sim.game_over());
+    assert!(sim.results_shown());
+
+    let step = sim.ticks();
+    assert!(!sim.play(&mut keys(&[(step, KeyCode::Enter)]), 50));
+    assert!(!sim.results_shown());
+    assert!(!sim.game_over());
+    assert_eq!(sim.ticks(), step + 50);
+}
@@ -0,0 +1,63 @@
+use std::collections::VecDeque;
+
+use pong_terminal::level::{BrickKind, Level, COLUMNS, MAX_ROWS};
+use proptest::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha12Rng;
+
+// Whether the ball can work its way round the obstacles to every breakable brick, coming
+// in over the top row or under the bottom one. Breakable bricks give way in the end
+fn reachable(level: &Level) -> bool {
+    let mut free = [[true; COLUMNS]; MAX_ROWS];
+    for brick in &level.bricks {
+        if brick.kind == BrickKind::Obstacle {
+            free[brick.row][brick.col] = false;
+        }
+    }
+
+    let mut seen = [[false; COLUMNS]; MAX_ROWS];
+    let mut queue: VecDeque<_> = [0, MAX_ROWS - 1]
+        .into_iter()
+        .flat_map(|row| (0..COLUMNS).map(move |col| (row, col)))
+        .filter(|&(row, col)| free[row][col])
+        .collect();
+    for &(row, col) in &queue {
+        seen[row][col] = true;
+    }
+    while let Some((row, col)) = queue.pop_front() {
+        let neighbours = [(row.wrapping_sub(1), col), (row + 1, col), (row, col.wrapping_sub(1)), (row, col + 1)];
+        for (r, c) in neighbours {
+            if r < MAX_ROWS && c < COLUMNS && free[r][c] && !seen[r][c] {
+                seen[r][c] = true;
+                queue.push_back((r, c));
+            }
+        }
+    }
+    level.bricks.iter().filter(|brick| brick.kind == BrickKind::Breakable).all(|brick| seen[brick.row][brick.col])
+}
+
+// The check above has to catch a brick boxed in when there is one
+#[test]
+fn obstacles_can_box_a_brick_in() {
+    assert!(!reachable(&Level::parse("XXX............\nX#X............\nXXX............\n").unwrap()));
+    assert!(!reachable(&Level::parse("XXXXXXXXXXXXXXX\n###############\nXXXXXXXXXXXXXXX\n").unwrap()));
+    assert!(reachable(&Level::parse("XXX............\nX#.............\nXXX............\n").unwrap()));
+    assert!(reachable(&Level::parse("#######.#######\nXXXXXXXXXXXXXXX\n###############\n").unwrap()));
+}
+
+// One the generator used to box a brick in on
+#[test]
+fn a_generated_level_with_obstacles_all_round_a_brick() {
+    let level = Level::generate(20, &mut ChaCha12Rng::seed_from_u64(0));
+    assert!(reachable(&level), "walled in:\n{}", level.to_text());
+}
+
+proptest! {
+    #[test]
+    fn generated_levels_can_be_cleared(seed in any::<u64>(), index in 1u32..40) {
+        let level = Level::generate(index, &mut ChaCha12Rng::seed_from_u64(seed));
+        prop_assert!(!level.cleared(), "level {} came with nothing to break", index);
+        prop_assert!(reachable(&level), "level {} has a brick walled in:\n{}", index, level.to_text());
+    }
+}
+
@@ -0,0 +1,125 @@
+use crossterm::event::KeyCode;
+use pong_terminal::{Config, GameEvent, Paddle, Simulation};
+use proptest::prelude::*;
+
+const TICKS: usize = 600;
+
+fn key() -> impl Strategy<Value = Option<KeyCode>> {
+    prop_oneof![
+        3 => Just(None),
+        1 => Just(Some(KeyCode::Left)),
+        1 => Just(Some(KeyCode::Right)),
+    ]
+}
+
+fn assert_in_court(sim: &Simulation) -> Result<(), TestCaseError> {
+    let (x, y) = sim.ball();
+    let (width, height) = sim.ball_size();
+    let court = sim.playground();
+    prop_assert!(x >= court.left() && x + width <= court.right(), "ball x {} out of {:?}", x, court);
+    prop_assert!(y >= court.bottom() && y + height <= court.top(), "ball y {} out of {:?}", y, court);
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn ball_never_leaves_the_court(
+        seed in any::<u64>(),
+        vx in 0.1f64..40.0,
+        vy in 0.1f64..40.0,
+        ball in 1.0f64..20.0,
+        court in (20u16..150, 20u16..100),
+        keys in prop::collection::vec(key(), TICKS),
+    ) {
+        let mut sim = Simulation::new(seed);
+        sim.set_speed(vx, vy, vx.max(vy));
+        sim.set_ball_size(ball, ball);
+        sim.set_court(court.0, court.1);
+        for key in keys {
+            if let Some(code) = key {
+                sim.press(code);
+            }
+            sim.tick();
+            assert_in_court(&sim)?;
+        }
+    }
+
+    // However fast the game runs, the ball never jumps further than --max-speed in a tick,
+    // which could take it through a brick or a paddle
+    #[test]
+    fn the_ball_moves_no_further_than_the_cap(
+        seed in any::<u64>(),
+        speed in 0.5f64..3.0,
+        vx in 0.1f64..40.0,
+        vy in 0.1f64..40.0,
+        max_speed in 1.0f64..10.0,
+    ) {
+        let mut sim = Simulation::with_config(Config { seed: Some(seed), procedural: true, speed: Some(speed), ..Config::default() });
+        sim.set_speed(vx, vy, max_speed);
+        for _ in 0..TICKS {
+            let (x, y) = sim.ball();
+            sim.tick();
+            let (to_x, to_y) = sim.ball();
+            prop_assert!((to_x - x).abs() <= max_speed + 1e-9, "ball went {} across", to_x - x);
+            prop_assert!((to_y - y).abs() <= max_speed + 1e-9, "ball went {} along", to_y - y);
+        }
+    }
+
+    #[test]
+    fn score_only_rises_on_a_point_or_a_return(
+        seed in any::<u64>(),
+        rally in any::<bool>(),
+        keys in prop::collection::vec(key(), TICKS),
+    ) {
+        let mut sim = if rally { Simulation::rally(seed) } else { Simulation::new(seed) };
+        for key in keys {
+            if let Some(code) = key {
+                sim.press(code);
+            }
+            let before = sim.score();
+            sim.tick();
+            if sim.game_over() {
+                break;
+            }
+            if sim.score() > before {
+                let scored = sim.events().iter().any(|event| match event {
+                    GameEvent::PointScored => true,
+                    GameEvent::BallHitPaddle { paddle: Paddle::Player, .. } => rally,
+                    _ => false,
+                });
+                prop_assert!(scored, "score rose to {} on {:?}", sim.score(), sim.events());
+            }
+        }
+    }
+
+    #[test]
+    fn speed_stays_finite(
+        seed in any::<u64>(),
+        vx in 0.1f64..1000.0,
+        vy in 0.1f64..1000.0,
+        max_speed in 1.0f64..1000.0,
+        court in (1u16..150, 1u16..100),
+    ) {
+        let mut sim = Simulation::procedural(seed);
+        sim.set_speed(vx, vy, max_speed);
+        sim.set_court(court.0, court.1);
+        for _ in 0..TICKS {
+            sim.tick();
+            let (vx, vy) = sim.velocity();
+            let (x, y) = sim.ball();
+            prop_assert!(vx.is_finite() && vy.is_finite(), "velocity ({}, {})", vx, vy);
+            prop_assert!(x.is_finite() && y.is_finite(), "ball at ({}, {})", x, y);
+        }
+    }
+}
+
+// The ball only has to catch the paddle's far end to come back, its x is its left edge
+#[test]
+fn the_ball_comes_back_off_the_end_of_the_paddle() {
+    let mut sim = Simulation::new(3);
+    sim.set_speed(0.0, 1.0, 4.0);
+    let bottom = sim.playground().bottom();
+    sim.set_ball(sim.paddle() + 8.0, bottom + 2.0);
+    sim.tick();
+    assert!(sim.events().contains(&GameEvent::BallHitPaddle { paddle: Paddle::Player, multiplier: 1 }), "{:?}", sim.events());
+}
@@ -0,0 +1,20 @@
+use pong_terminal::LevelProgress;
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn level_progress_stays_within_the_level(tick in any::<u64>()) {
+        let progress = LevelProgress::at(tick);
+        prop_assert!(progress.percent() <= 100);
+        prop_assert!((0.0..1.0).contains(&progress.ratio()));
+        prop_assert!(progress.level >= 1);
+    }
+}
+
+#[test]
+fn level_progress_starts_over_at_every_speed_up() {
+    assert_eq!(LevelProgress::at(0).percent(), 0);
+    assert_eq!(LevelProgress::at(1023).percent(), 99);
+    assert_eq!(LevelProgress::at(1024).level, 2);
+    assert_eq!(LevelProgress::at(1024).percent(), 0);
+}
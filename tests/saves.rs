@@ -0,0 +1,96 @@
+use crossterm::event::KeyCode;
+use pong_terminal::{Config, Simulation};
+
+const SEED: u64 = 3;
+
+fn played(mut sim: Simulation, ticks: usize) -> Simulation {
+    for n in 0..ticks {
+        if n % 7 == 0 {
+            sim.press(if n % 2 == 0 { KeyCode::Left } else { KeyCode::Right });
+        }
+        sim.tick();
+    }
+    sim
+}
+
+// Loading a save and saving again gives back the very same save
+fn assert_round_trip(sim: &Simulation) {
+    let json = sim.save().unwrap();
+    let mut resumed = Simulation::new(SEED + 1);
+    resumed.load(&json).unwrap();
+    assert_eq!(resumed.save().unwrap(), json);
+    assert_eq!(resumed.ball(), sim.ball());
+    assert_eq!(resumed.paddle(), sim.paddle());
+    assert_eq!(resumed.score(), sim.score());
+}
+
+#[test]
+fn a_save_comes_back_as_it_was() {
+    assert_round_trip(&played(Simulation::new(SEED), 500));
+}
+
+#[test]
+fn a_procedural_save_keeps_its_bricks() {
+    assert_round_trip(&played(Simulation::procedural(SEED), 500));
+}
+
+#[test]
+fn a_target_practice_save_keeps_its_rings() {
+    let mut sim = played(Simulation::targets(SEED), 300);
+    let json = sim.save().unwrap();
+    assert!(json.matches("\"kind\":\"Ring\"").count() > 1, "not enough rings up to save");
+    assert_round_trip(&sim);
+
+    let mut resumed = Simulation::targets(SEED + 1);
+    resumed.load(&json).unwrap();
+    for _ in 0..600 {
+        sim.tick();
+        resumed.tick();
+    }
+    assert_eq!(resumed.save().unwrap(), sim.save().unwrap());
+}
+
+// Picked up from the save, the game goes on the way it would have without one
+#[test]
+fn a_resumed_game_carries_on_the_same() {
+    let mut sim = played(Simulation::new(SEED), 400);
+    let mut resumed = Simulation::new(SEED + 1);
+    resumed.load(&sim.save().unwrap()).unwrap();
+    for _ in 0..400 {
+        sim.tick();
+        resumed.tick();
+        assert_eq!(resumed.ball(), sim.ball());
+    }
+    assert_eq!(resumed.score(), sim.score());
+}
+
+// A sideways hardcore first to 3 at one and a half times the speed, picked up from a plain
+// command line, still plays by its own rules
+#[test]
+fn a_resumed_game_keeps_its_rules() {
+    let config = Config {
+        seed: Some(SEED),
+        win_score: Some(3),
+        hardcore: true,
+        speed: Some(1.5),
+        sideways: true,
+        ..Config::default()
+    };
+    let mut sim = played(Simulation::with_config(config), 300);
+    let json = sim.save().unwrap();
+    let mut resumed = Simulation::new(SEED + 1);
+    resumed.load(&json).unwrap();
+    assert_eq!(resumed.save().unwrap(), json);
+    for _ in 0..300 {
+        sim.tick();
+        resumed.tick();
+        assert_eq!(resumed.ball(), sim.ball());
+    }
+    assert_eq!(resumed.game_over(), sim.game_over());
+}
+
+#[test]
+fn a_broken_save_is_an_error() {
+    let mut sim = Simulation::new(SEED);
+    assert!(sim.load("{\"ball\":").is_err());
+}
@@ -0,0 +1,103 @@
+use std::{env, fs, path::PathBuf};
+
+use crossterm::event::KeyCode;
+use pong_terminal::{render::TestRenderer, Simulation};
+
+const SEED: u64 = 1;
+
+// Compares a rendered frame with tests/snapshots/<name>.txt.
+// Run with UPDATE_SNAPSHOTS=1 to rewrite the golden files after an intended change.
+fn assert_snapshot(name: &str, frame: &str) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{}.txt", name));
+
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, frame).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("missing snapshot {}, run with UPDATE_SNAPSHOTS=1", path.display()));
+    assert!(
+        expected == frame,
+        "{} changed\n--- expected\n{}\n--- actual\n{}",
+        name,
+        expected,
+        frame
+    );
+}
+
+fn render(sim: &Simulation, width: u16, height: u16) -> String {
+    let mut renderer = TestRenderer::new(width, height);
+    sim.render(&mut renderer).unwrap();
+    renderer.last_frame().unwrap().to_string()
+}
+
+fn playing() -> Simulation {
+    let mut sim = Simulation::new(SEED);
+    for _ in 0..100 {
+        sim.tick();
+    }
+    sim
+}
+
+#[test]
+fn play_screen() {
+    assert_snapshot("play", &render(&playing(), 80, 24));
+}
+
+#[test]
+fn win_screen() {
+    let mut sim = playing();
+    sim.set_score(10);
+    sim.tick();
+    assert_snapshot("win", &render(&sim, 80, 24));
+}
+
+#[test]
+fn pause_overlay() {
+    let mut sim = playing();
+    sim.press(KeyCode::Char('p'));
+    assert_snapshot("paused", &render(&sim, 80, 24));
+}
+
+#[test]
+fn menu_screen() {
+    let mut sim = playing();
+    sim.press(KeyCode::Esc);
+    assert_snapshot("menu", &render(&sim, 80, 24));
+}
+
+#[test]
+fn help_lists_the_keys_as_mapped() {
+    let mut sim = playing();
+    sim.press(KeyCode::Char('?'));
+    assert!(render(&sim, 80, 24).contains("← / →  move your paddle left / right"));
+
+    let mut sim = playing();
+    sim.set_mirrored(true);
+    sim.press(KeyCode::Char('?'));
+    let frame = render(&sim, 80, 24);
+    assert!(frame.contains("→ / ←  move your paddle left / right"), "{}", frame);
+}
+
+#[test]
+fn hud_survives_small_terminals() {
+    let sim = playing();
+    for (width, height) in [(40, 12), (60, 13), (100, 14)] {
+        let frame = render(&sim, width, height);
+        assert!(frame.contains("Score"), "score gauge missing at {}x{}\n{}", width, height, frame);
+        assert!(frame.contains("Level"), "level gauge missing at {}x{}\n{}", width, height, frame);
+    }
+}
+
+#[test]
+fn too_small_terminal() {
+    let sim = playing();
+    for (width, height) in [(39, 24), (80, 11), (20, 5), (0, 0)] {
+        let frame = render(&sim, width, height);
+        assert!(!frame.contains("Score"), "court drawn at {}x{}\n{}", width, height, frame);
+    }
+    assert_snapshot("too_small", &render(&sim, 30, 8));
+}